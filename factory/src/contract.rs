@@ -0,0 +1,173 @@
+//! EnerDAO project factory
+use crate::admin::{has_administrator, read_administrator, write_administrator};
+use crate::errors::Error;
+use crate::project_token::ProjectTokenClient;
+use crate::registry::{add_project, read_number_of_projects, read_project, read_project_id};
+use crate::storage_types::{
+    DataKey, ProjectInfo, ProjectRecord, RepaymentSchedule, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD, MAX_PAGE_SIZE,
+};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, Address, BytesN, Env, String, Symbol, Vec,
+};
+
+fn read_token_wasm_hash(e: &Env) -> BytesN<32> {
+    let key = DataKey::TokenWasmHash;
+    match e.storage().instance().get(&key) {
+        Some(hash) => hash,
+        None => panic_with_error!(e, Error::NotInitialized),
+    }
+}
+
+fn write_token_wasm_hash(e: &Env, hash: &BytesN<32>) {
+    let key = DataKey::TokenWasmHash;
+    e.storage().instance().set(&key, hash);
+}
+
+// Salt is derived from the project id only, so the address of
+// every project is known before it is deployed
+fn project_salt(e: &Env, id: u32) -> BytesN<32> {
+    let mut salt = [0u8; 32];
+    salt[28..].copy_from_slice(&id.to_be_bytes());
+    BytesN::from_array(e, &salt)
+}
+
+#[contract]
+pub struct EnerDAOFactory;
+
+#[contractimpl]
+impl EnerDAOFactory {
+    pub fn initialize(e: Env, admin: Address, token_wasm_hash: BytesN<32>) {
+        if has_administrator(&e) {
+            panic_with_error!(&e, Error::AlreadyInitialized)
+        }
+        write_administrator(&e, &admin);
+        write_token_wasm_hash(&e, &token_wasm_hash);
+    }
+
+    pub fn set_admin(e: Env, new_admin: Address) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_administrator(&e, &new_admin);
+        e.events()
+            .publish((Symbol::new(&e, "set_admin"), admin), new_admin);
+    }
+
+    pub fn set_token_wasm_hash(e: Env, token_wasm_hash: BytesN<32>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        write_token_wasm_hash(&e, &token_wasm_hash);
+        e.events()
+            .publish((Symbol::new(&e, "set_token_wasm_hash"),), token_wasm_hash);
+    }
+
+    // Deploys a new EnerDAOToken, initializes it and sets up the project
+    // in one transaction. `admin` becomes the administrator of the project
+    // and has to authorize the `init_project` call.
+    pub fn create_project(
+        e: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+        project_info: ProjectInfo,
+        schedule: Option<RepaymentSchedule>,
+    ) -> Address {
+        let factory_admin = read_administrator(&e);
+        factory_admin.require_auth();
+        if admin != factory_admin {
+            admin.require_auth();
+        }
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let id: u32 = read_number_of_projects(&e) + 1;
+        let address: Address = e
+            .deployer()
+            .with_current_contract(project_salt(&e, id))
+            .deploy(read_token_wasm_hash(&e));
+
+        let project = ProjectTokenClient::new(&e, &address);
+        project.initialize(&admin, &decimal, &name, &symbol);
+        project.init_project(
+            &project_info.borrower,
+            &project_info.lend_token_address,
+            &project_info.collateral_nft_address,
+            &project_info.collateral_id,
+            &project_info.target_amount,
+            &project_info.start_timestamp,
+            &project_info.final_timestamp,
+            &project_info.reward_rate,
+            &project_info.treasury_address,
+            &schedule,
+        );
+
+        let record = ProjectRecord {
+            id,
+            address: address.clone(),
+            admin,
+            borrower: project_info.borrower,
+            created_at: e.ledger().timestamp(),
+        };
+        add_project(&e, &record);
+
+        e.events()
+            .publish((Symbol::new(&e, "project_created"), id), address.clone());
+
+        address
+    }
+
+    pub fn project_address(e: Env, id: u32) -> Address {
+        e.deployer()
+            .with_current_contract(project_salt(&e, id))
+            .deployed_address()
+    }
+
+    pub fn token_wasm_hash(e: Env) -> BytesN<32> {
+        read_token_wasm_hash(&e)
+    }
+
+    pub fn number_of_projects(e: Env) -> u32 {
+        read_number_of_projects(&e)
+    }
+
+    pub fn get_project(e: Env, id: u32) -> ProjectRecord {
+        match read_project(&e, id) {
+            Some(record) => record,
+            None => panic_with_error!(&e, Error::ProjectNotFound),
+        }
+    }
+
+    pub fn get_project_id(e: Env, address: Address) -> u32 {
+        match read_project_id(&e, address) {
+            Some(id) => id,
+            None => panic_with_error!(&e, Error::ProjectNotFound),
+        }
+    }
+
+    // Returns at most `limit` projects (capped at MAX_PAGE_SIZE),
+    // skipping the first `offset` ones
+    pub fn get_projects(e: Env, offset: u32, limit: u32) -> Vec<ProjectRecord> {
+        let mut projects: Vec<ProjectRecord> = Vec::<ProjectRecord>::new(&e);
+        let number_of_projects: u32 = read_number_of_projects(&e);
+        let limit: u32 = limit.min(MAX_PAGE_SIZE);
+        let first: u32 = offset.saturating_add(1);
+        let last: u32 = offset.saturating_add(limit).min(number_of_projects);
+        for id in first..=last {
+            projects.push_back(read_project(&e, id).unwrap());
+        }
+        projects
+    }
+}
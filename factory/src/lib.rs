@@ -0,0 +1,11 @@
+#![no_std]
+
+mod admin;
+mod contract;
+mod errors;
+mod project_token;
+mod registry;
+mod storage_types;
+mod test;
+
+pub use crate::contract::EnerDAOFactoryClient;
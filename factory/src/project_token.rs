@@ -0,0 +1,24 @@
+use crate::storage_types::RepaymentSchedule;
+use soroban_sdk::{contractclient, Address, Env, String};
+
+// Subset of the EnerDAOToken interface used to set up a freshly deployed project.
+#[allow(dead_code)]
+#[contractclient(name = "ProjectTokenClient")]
+pub trait ProjectTokenInterface {
+    fn initialize(e: Env, admin: Address, decimal: u32, name: String, symbol: String);
+
+    #[allow(clippy::too_many_arguments)]
+    fn init_project(
+        e: Env,
+        borrower: Address,
+        lend_token_address: Address,
+        collateral_nft_address: Address,
+        collateral_id: u128,
+        target_amount: i128,
+        start_timestamp: u64,
+        final_timestamp: u64,
+        reward_rate: i128,
+        treasury_address: Address,
+        schedule: Option<RepaymentSchedule>,
+    );
+}
@@ -0,0 +1,48 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{
+    DataKey, ProjectRecord, PROJECT_BUMP_AMOUNT, PROJECT_LIFETIME_THRESHOLD,
+};
+
+pub fn read_number_of_projects(e: &Env) -> u32 {
+    let key = DataKey::NumberOfProjects;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn write_number_of_projects(e: &Env, val: u32) {
+    let key = DataKey::NumberOfProjects;
+    e.storage().instance().set(&key, &val);
+}
+
+pub fn read_project(e: &Env, id: u32) -> Option<ProjectRecord> {
+    let key = DataKey::Project(id);
+    let record: Option<ProjectRecord> = e.storage().persistent().get(&key);
+    if record.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, PROJECT_LIFETIME_THRESHOLD, PROJECT_BUMP_AMOUNT);
+    }
+    record
+}
+
+pub fn read_project_id(e: &Env, address: Address) -> Option<u32> {
+    let key = DataKey::ProjectId(address);
+    e.storage().persistent().get(&key)
+}
+
+// Records a newly deployed project under the next free id, ids start from 1
+pub fn add_project(e: &Env, record: &ProjectRecord) {
+    let key = DataKey::Project(record.id);
+    e.storage().persistent().set(&key, record);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, PROJECT_LIFETIME_THRESHOLD, PROJECT_BUMP_AMOUNT);
+
+    let key_id = DataKey::ProjectId(record.address.clone());
+    e.storage().persistent().set(&key_id, &record.id);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key_id, PROJECT_LIFETIME_THRESHOLD, PROJECT_BUMP_AMOUNT);
+
+    write_number_of_projects(e, record.id);
+}
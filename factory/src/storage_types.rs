@@ -0,0 +1,69 @@
+use soroban_sdk::{contracttype, Address, Vec};
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const PROJECT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PROJECT_LIFETIME_THRESHOLD: u32 = PROJECT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const MAX_PAGE_SIZE: u32 = 50;
+
+// Mirrors `ProjectInfo` of the EnerDAOToken contract, it is passed
+// through to `init_project` of every deployed project.
+#[derive(Clone)]
+#[contracttype]
+pub struct ProjectInfo {
+    pub borrower: Address,
+    pub lend_token_address: Address,
+    pub collateral_nft_address: Address,
+    pub collateral_id: u128,
+    pub target_amount: i128,
+    pub start_timestamp: u64,
+    pub final_timestamp: u64,
+    pub reward_rate: i128,
+    pub treasury_address: Address,
+}
+
+// Mirror `RepaymentSchedule` of the EnerDAOToken contract
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Installment {
+    pub due_timestamp: u64,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmortizationParams {
+    pub first_due_timestamp: u64,
+    pub period: u64,
+    pub number_of_installments: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RepaymentSchedule {
+    Installments(Vec<Installment>),
+    Amortized(AmortizationParams),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProjectRecord {
+    pub id: u32,
+    pub address: Address,
+    pub admin: Address,
+    pub borrower: Address,
+    pub created_at: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    TokenWasmHash,
+    NumberOfProjects,
+    Project(u32),
+    ProjectId(Address),
+}
@@ -0,0 +1,165 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{
+    contract::EnerDAOFactory, contract::EnerDAOFactoryClient,
+    storage_types::{AmortizationParams, ProjectInfo, RepaymentSchedule},
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, LedgerInfo},
+    token, Address, Env, IntoVal,
+};
+
+mod project_contract {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/release/enerdao_token_contract.wasm"
+    );
+}
+
+use token::Client as TokenClient;
+use token::StellarAssetClient as TokenAdminClient;
+
+fn create_token<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, TokenAdminClient<'a>) {
+    let contract_address = e.register_stellar_asset_contract(admin.clone());
+    (
+        TokenClient::new(e, &contract_address),
+        TokenAdminClient::new(e, &contract_address),
+    )
+}
+
+fn create_factory<'a>(e: &Env, admin: &Address) -> EnerDAOFactoryClient<'a> {
+    let wasm_hash = e.deployer().upload_contract_wasm(project_contract::WASM);
+    let factory = EnerDAOFactoryClient::new(e, &e.register_contract(None, EnerDAOFactory {}));
+    factory.initialize(admin, &wasm_hash);
+    factory
+}
+
+fn project_info(e: &Env, borrower: &Address, lend_token: &Address, target_amount: i128) -> ProjectInfo {
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    ProjectInfo {
+        borrower: borrower.clone(),
+        lend_token_address: lend_token.clone(),
+        collateral_nft_address: lend_token.clone(),
+        collateral_id: 0,
+        target_amount,
+        start_timestamp: current_timestamp,
+        final_timestamp: current_timestamp + 1000_u64,
+        reward_rate: 1000,
+        treasury_address: borrower.clone(),
+    }
+}
+
+#[test]
+fn test_create_project() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let project_admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let (eurc_token, eurc_admin) = create_token(&e, &admin);
+    eurc_admin.mint(&lender, &1000_0000000i128);
+
+    let factory = create_factory(&e, &admin);
+    assert_eq!(factory.number_of_projects(), 0);
+
+    let expected_address = factory.project_address(&1);
+    let info = project_info(&e, &borrower, &eurc_token.address, 1000_0000000i128);
+    let address = factory.create_project(
+        &project_admin,
+        &7,
+        &"LP EnerDAO".into_val(&e),
+        &"LPE".into_val(&e),
+        &info,
+        &Some(RepaymentSchedule::Amortized(AmortizationParams {
+            first_due_timestamp: info.final_timestamp + 1000,
+            period: 1000,
+            number_of_installments: 4,
+        })),
+    );
+    assert_eq!(address, expected_address);
+    assert_eq!(factory.number_of_projects(), 1);
+    assert_eq!(factory.get_project_id(&address), 1);
+
+    let record = factory.get_project(&1);
+    assert_eq!(record.address, address);
+    assert_eq!(record.admin, project_admin);
+    assert_eq!(record.borrower, borrower);
+
+    // deployed project is ready to accept lenders
+    let project = project_contract::Client::new(&e, &address);
+    assert_eq!(project.get_project_info().target_amount, 1000_0000000i128);
+    assert_eq!(project.get_project_info().borrower, borrower);
+    assert_eq!(project.symbol(), "LPE".into_val(&e));
+    assert_eq!(project.schedule().len(), 4);
+
+    project.lend(&lender, &0, &1000_0000000i128);
+    assert_eq!(project.balance(&lender), 1000_0000000i128);
+    assert_eq!(eurc_token.balance(&address), 1000_0000000i128);
+}
+
+#[test]
+fn test_get_projects() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let (eurc_token, _) = create_token(&e, &admin);
+
+    let factory = create_factory(&e, &admin);
+    for i in 1..=5 {
+        let info = project_info(&e, &borrower, &eurc_token.address, i * 100_0000000i128);
+        factory.create_project(
+            &admin,
+            &7,
+            &"LP EnerDAO".into_val(&e),
+            &"LPE".into_val(&e),
+            &info,
+            &None,
+        );
+    }
+    assert_eq!(factory.number_of_projects(), 5);
+
+    let page = factory.get_projects(&0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().id, 1);
+    assert_eq!(page.get(1).unwrap().id, 2);
+
+    let page = factory.get_projects(&3, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().id, 4);
+    assert_eq!(page.get(1).unwrap().address, factory.project_address(&5));
+
+    assert_eq!(factory.get_projects(&5, &10).len(), 0);
+    assert_eq!(factory.get_projects(&u32::MAX, &u32::MAX).len(), 0);
+
+    let project = project_contract::Client::new(&e, &factory.project_address(&3));
+    assert_eq!(project.get_project_info().target_amount, 300_0000000i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #100)")]
+fn initialize_already_initialized() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let factory = create_factory(&e, &admin);
+
+    let wasm_hash = factory.token_wasm_hash();
+    factory.initialize(&admin, &wasm_hash);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #102)")]
+fn get_missing_project() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let factory = create_factory(&e, &admin);
+
+    factory.get_project(&1);
+}
@@ -6,10 +6,14 @@ use crate::metadata::{
     read_token_uri, write_token_uri
 };
 use crate::balance::{read_supply, increment_supply};
+use crate::upgrade::{
+    migrate, read_schema_version, write_schema_version,
+    CODE_VERSION, SCHEMA_VERSION
+};
 use soroban_sdk::{
-    contract, contractimpl, log, Address, Env, String
+    contract, contractimpl, log, Address, BytesN, Env, String, Symbol
 };
-use crate::storage_types::{DataKey, UserDataKey};
+use crate::storage_types::{DataKey, UserDataKey, Version};
 
 #[contract]
 pub struct NonFungibleToken;
@@ -24,6 +28,32 @@ impl NonFungibleToken {
         write_admin(&env, &admin);
         write_name(&env, &name);
         write_symbol(&env, &symbol);
+        write_schema_version(&env, SCHEMA_VERSION);
+    }
+
+    // Replaces the contract code, the storage is kept as is
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((Symbol::new(&env, "upgrade"), admin), new_wasm_hash);
+    }
+
+    // Rewrites the storage written by an older code version to the current layout
+    pub fn migrate(env: Env) {
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        let from = migrate(&env);
+        env.events().publish((Symbol::new(&env, "migrate"), admin), (from, SCHEMA_VERSION));
+    }
+
+    pub fn version(env: Env) -> Version {
+        Version {
+            code_version: CODE_VERSION,
+            schema_version: read_schema_version(&env),
+        }
     }
 
     pub fn name(env: Env) -> String {
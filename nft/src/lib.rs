@@ -5,7 +5,9 @@ mod balance;
 mod owner;
 mod metadata;
 mod contract;
+mod errors;
 mod storage_types;
+mod upgrade;
 mod test;
 
 pub use crate::contract::NonFungibleTokenClient;
@@ -12,6 +12,7 @@ pub enum DataKey {
     Approval(ApprovalKey),
     Owner(u128),
     Supply,
+    SchemaVersion,
 }
 
 #[derive(Clone)]
@@ -25,4 +26,11 @@ pub enum UserDataKey {
 pub struct ApprovalKey {
     owner: Address,
     spender: Address,
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Version {
+    pub code_version: u32,
+    pub schema_version: u32,
+}
@@ -3,7 +3,7 @@
 
 extern crate std;
 
-use crate::{contract::NonFungibleToken, NonFungibleTokenClient};
+use crate::{contract::NonFungibleToken, errors::Error, storage_types::Version, NonFungibleTokenClient};
 use soroban_sdk::{
     testutils::{Address as _, Logs},
     Address, Env, IntoVal, String
@@ -83,4 +83,24 @@ fn seat_already_taken() {
 
     nft.mint(&user2, &1, &String::from_str(&env, "https://music.youtube.com/watch?v=yRVotpLaCD4"));
     std::println!("{}", env.logs().all().join("\n"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let nft = create_token(&env, &admin);
+
+    assert_eq!(nft.version(), Version { code_version: 2, schema_version: 1 });
+}
+
+#[test]
+fn test_migrate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let nft = create_token(&env, &admin);
+
+    assert_eq!(nft.try_migrate(), Err(Ok(Error::AlreadyMigrated.into())));
+}
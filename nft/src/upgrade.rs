@@ -0,0 +1,30 @@
+use soroban_sdk::{panic_with_error, Env};
+use crate::errors::Error;
+use crate::storage_types::DataKey;
+
+// Version of the contract code, bumped with every release
+pub(crate) const CODE_VERSION: u32 = 2;
+// Version of the storage layout, the layout has not changed since the first release
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+// Contracts deployed before the schema version was stored use the first layout
+pub fn read_schema_version(e: &Env) -> u32 {
+    let key = DataKey::SchemaVersion;
+    e.storage().instance().get(&key).unwrap_or(1)
+}
+
+pub fn write_schema_version(e: &Env, version: u32) {
+    let key = DataKey::SchemaVersion;
+    e.storage().instance().set(&key, &version);
+}
+
+// Runs the migrations from the stored schema version up to SCHEMA_VERSION,
+// returns the version the data was migrated from
+pub fn migrate(e: &Env) -> u32 {
+    let from: u32 = read_schema_version(e);
+    if from >= SCHEMA_VERSION {
+        panic_with_error!(e, Error::AlreadyMigrated)
+    }
+    write_schema_version(e, SCHEMA_VERSION);
+    from
+}
@@ -1,6 +1,9 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{panic_with_error, Address, Env};
 
-use crate::storage_types::DataKey;
+use crate::errors::Error;
+use crate::storage_types::{
+    DataKey, Role, RoleDataKey, RoleHolderDataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
 
 pub fn has_administrator(e: &Env) -> bool {
     let key = DataKey::Admin;
@@ -9,10 +12,80 @@ pub fn has_administrator(e: &Env) -> bool {
 
 pub fn read_administrator(e: &Env) -> Address {
     let key = DataKey::Admin;
-    e.storage().instance().get(&key).unwrap()
+    match e.storage().instance().get(&key) {
+        Some(admin) => admin,
+        None => panic_with_error!(e, Error::NotInitialized),
+    }
 }
 
 pub fn write_administrator(e: &Env, id: &Address) {
     let key = DataKey::Admin;
     e.storage().instance().set(&key, id);
 }
+
+fn role_holder_key(role: Role, account: &Address) -> RoleDataKey {
+    RoleDataKey::Holder(RoleHolderDataKey {
+        role,
+        account: account.clone(),
+    })
+}
+
+pub fn has_role(e: &Env, role: Role, account: &Address) -> bool {
+    let key = role_holder_key(role, account);
+    e.storage().persistent().has(&key)
+}
+
+pub fn read_role_holder_count(e: &Env, role: Role) -> u32 {
+    let key = RoleDataKey::HolderCount(role);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn write_role_holder_count(e: &Env, role: Role, count: u32) {
+    let key = RoleDataKey::HolderCount(role);
+    e.storage().persistent().set(&key, &count);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Adds `account` to the holders of the role, false if it already holds it
+pub fn write_role_holder(e: &Env, role: Role, account: &Address) -> bool {
+    if has_role(e, role, account) {
+        return false;
+    }
+    let key = role_holder_key(role, account);
+    e.storage().persistent().set(&key, &true);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    write_role_holder_count(e, role, read_role_holder_count(e, role) + 1);
+    true
+}
+
+pub fn remove_role_holder(e: &Env, role: Role, account: &Address) {
+    if !has_role(e, role, account) {
+        panic_with_error!(e, Error::RoleNotGranted)
+    }
+    let key = role_holder_key(role, account);
+    e.storage().persistent().remove(&key);
+    write_role_holder_count(e, role, read_role_holder_count(e, role) - 1);
+}
+
+// Every holder of the role can act in it, the administrator
+// only while nobody holds the role
+pub fn require_role(e: &Env, role: Role, caller: &Address) {
+    caller.require_auth();
+    if has_role(e, role, caller) {
+        return;
+    }
+    if read_role_holder_count(e, role) > 0 || *caller != read_administrator(e) {
+        panic_with_error!(e, Error::RoleNotGranted)
+    }
+}
+
+pub fn require_admin(e: &Env, caller: &Address) {
+    caller.require_auth();
+    if *caller != read_administrator(e) {
+        panic_with_error!(e, Error::NotAllowed)
+    }
+}
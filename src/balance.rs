@@ -1,5 +1,7 @@
+use crate::errors::Error;
+use crate::governance::write_balance_checkpoint;
 use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{panic_with_error, Address, Env};
 
 pub fn read_balance(e: &Env, addr: Address) -> i128 {
     let key = DataKey::Balance(addr);
@@ -14,14 +16,18 @@ pub fn read_balance(e: &Env, addr: Address) -> i128 {
 }
 
 fn write_balance(e: &Env, addr: Address, amount: i128) {
-    let key = DataKey::Balance(addr);
+    let key = DataKey::Balance(addr.clone());
     e.storage().persistent().set(&key, &amount);
     e.storage()
         .persistent()
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    write_balance_checkpoint(e, addr, amount);
 }
 
 pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
+    if amount == 0 {
+        return;
+    }
     let balance = read_balance(e, addr.clone());
     write_balance(e, addr, balance + amount);
 }
@@ -29,7 +35,10 @@ pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
 pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
     let balance = read_balance(e, addr.clone());
     if balance < amount {
-        panic!("insufficient balance");
+        panic_with_error!(e, Error::InsufficientBalance)
+    }
+    if amount == 0 {
+        return;
     }
     write_balance(e, addr, balance - amount);
 }
@@ -0,0 +1,62 @@
+use crate::storage_types::{
+    CollateralStatus, DataKey, DefaultConfig, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env};
+
+// Longest grace period a payment can be late by before the default
+pub(crate) const MAX_GRACE_PERIOD: u64 = 365 * 24 * 60 * 60;
+
+pub fn read_default_config(e: &Env) -> Option<DefaultConfig> {
+    let key = DataKey::DefaultConfig;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_default_config(e: &Env, config: &DefaultConfig) {
+    let key = DataKey::DefaultConfig;
+    e.storage().persistent().set(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn read_collateral_vault(e: &Env) -> Option<Address> {
+    let key = DataKey::CollateralVault;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_collateral_vault(e: &Env, vault: &Address) {
+    let key = DataKey::CollateralVault;
+    e.storage().persistent().set(&key, vault);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Only the final outcomes are stored, Held and Missing
+// depend on the current owner of the NFT
+pub fn read_collateral_outcome(e: &Env) -> Option<CollateralStatus> {
+    let key = DataKey::CollateralStatus;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_collateral_outcome(e: &Env, status: CollateralStatus) {
+    let key = DataKey::CollateralStatus;
+    e.storage().persistent().set(&key, &status);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Whoever moved the NFT in through deposit_collateral, it goes back to them
+pub fn read_collateral_depositor(e: &Env) -> Option<Address> {
+    let key = DataKey::CollateralDepositor;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_collateral_depositor(e: &Env, depositor: &Address) {
+    let key = DataKey::CollateralDepositor;
+    e.storage().persistent().set(&key, depositor);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
@@ -1,23 +1,72 @@
 //! EnerDAO tokenized funding
-use crate::admin::{has_administrator, read_administrator, write_administrator};
+use crate::admin::{
+    has_administrator, has_role, read_administrator, read_role_holder_count, remove_role_holder, require_admin,
+    require_role, write_administrator, write_role_holder,
+};
 use crate::allowance::{read_allowance, spend_allowance, write_allowance};
 use crate::balance::{read_balance, receive_balance, spend_balance};
+use crate::collateral::{
+    read_collateral_depositor, read_collateral_outcome, read_collateral_vault,
+    read_default_config, write_collateral_depositor, write_collateral_outcome,
+    write_collateral_vault, write_default_config, MAX_GRACE_PERIOD,
+};
+use crate::fee::{read_fee_config, read_protocol_fee, write_fee_config};
+use crate::funding::{
+    read_min_amount, read_pledge_lock_period, write_min_amount, write_pledge_lock_period,
+};
+use crate::governance::{
+    has_voted, next_proposal_id, proposal_status, read_balance_before, read_governance_config,
+    read_proposal, read_supply_before, write_governance_config, write_proposal,
+    write_supply_checkpoint, write_vote,
+};
+use crate::interest::{
+    accrue, is_accruing, read_interest_config, read_loan, repay_loan, write_interest_config,
+    write_loan, MAX_APR,
+};
+use crate::kyc::{
+    is_kyc_approved, read_kyc_provider, read_kyc_provider_or_panic, read_kyc_required, read_kyc_status,
+    remove_kyc_approval, require_kyc, write_kyc_approval, write_kyc_provider, write_kyc_required,
+};
+use crate::math::{mul_div, Rounding};
 use crate::metadata::{read_decimal, read_name, read_symbol, write_metadata};
+use crate::pause::{
+    is_emergency, is_paused, read_max_pause_duration, read_paused_at, remove_paused_at,
+    require_not_paused, write_max_pause_duration, write_paused_at,
+};
+use crate::schedule::{init_schedule, installment_infos, read_schedule, scale_schedule};
+use crate::state::{read_state, transition};
+use crate::timelock::{
+    has_queued_action, read_pending_admin, read_queued_action, read_timelock_delay,
+    remove_pending_admin, remove_queued_action, require_no_timelock, timelock_eta,
+    write_pending_admin, write_queued_action, write_timelock_delay,
+};
+use crate::tranche::{
+    add_tranche_paid, add_tranche_repaid, add_tranche_return, add_tranche_supply, book_dust,
+    distribute_return, has_pending_returns, number_of_tranches, read_dust, read_pending_return,
+    read_tranche, read_tranche_balance, read_tranche_paid, read_tranche_principal_repaid,
+    read_tranche_raised, read_tranche_repaid, read_tranche_return, read_tranche_supply,
+    read_tranches, read_unclaimed_return, receive_tranche_balance, settle, spend_pending_return,
+    spend_tranche_balance, write_tranche_principal_repaid, write_tranche_raised, write_tranches,
+};
+use crate::upgrade::{
+    migrate, read_schema_version, write_schema_version, CODE_VERSION, SCHEMA_VERSION,
+};
 #[cfg(test)]
 use crate::storage_types::{AllowanceDataKey, AllowanceValue};
 use crate::storage_types::{
-    DataKey, ProjectInfo, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT,
-    INSTANCE_LIFETIME_THRESHOLD,
+    AdminAction, BorrowerClaimStatus, BorrowerReturnStatus, LendStatus, LenderClaimStatus, GovernanceConfig, Proposal, ProposalAction, ProposalStatus, CollateralStatus, DataKey, DefaultConfig, FeeConfig, InstallmentInfo, InstallmentStatus,
+    InterestConfig, InterestMode, KycStatus, LenderPosition, Loan, PausableFunction, PendingAdmin, ProjectField, ProjectInfo, ProjectState, QueuedAction, Role,
+    ProjectSummary, RepaymentSchedule, Tranche, Version, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, MAX_PAGE_SIZE, MAX_REWARD_RATE, REWARD_DENOM,
 };
 use soroban_sdk::token::{self, Interface as _};
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, vec, Address, BytesN, Env, String, Symbol, Vec,
+};
 use soroban_token_sdk::metadata::TokenMetadata;
 use soroban_token_sdk::TokenUtils;
 
-use crate::errors::Error;
-
-const PROTOCOL_FEE: i128 = 1000;
-const REWARD_DENOM: i128 = 10000;
+use crate::errors::{DefaultError, Error, GovernanceError, ProjectInfoError};
 
 mod contract_nft {
     soroban_sdk::contractimport!(file = "./token/non_fungible_token.optimized.wasm");
@@ -29,24 +78,22 @@ fn check_nonnegative_amount(e: &Env, amount: i128) {
     }
 }
 
+// Checks shared by the entrypoints and their dry-run status queries,
+// so a status query returns the exact error the call would fail with
+fn check(e: &Env, result: Result<(), Error>) {
+    if let Err(error) = result {
+        panic_with_error!(e, error)
+    }
+}
+
 fn get_project_info(e: &Env) -> ProjectInfo {
     let key = DataKey::ProjectInfo;
     let project_info: ProjectInfo = e.storage().persistent().get(&key).unwrap();
     return project_info;
 }
 
-fn require_start_time_reached(e: &Env) {
-    let start_time: u64 = get_project_info(e).start_timestamp;
-    if e.ledger().timestamp() < start_time {
-        panic_with_error!(e, Error::NotStarted)
-    }
-}
-
-fn require_final_time_not_reached(e: &Env) {
-    let final_time: u64 = get_project_info(e).final_timestamp;
-    if e.ledger().timestamp() > final_time {
-        panic_with_error!(e, Error::AlreadyFinished)
-    }
+fn is_after_final_time(e: &Env) -> bool {
+    e.ledger().timestamp() > get_project_info(e).final_timestamp
 }
 
 fn require_final_time_reached(e: &Env) {
@@ -56,28 +103,395 @@ fn require_final_time_reached(e: &Env) {
     }
 }
 
-fn require_target_amount_reached(e: &Env) {
-    let target_amount: i128 = get_project_info(e).target_amount;
-    if read_total_supply(&e) < target_amount {
-        panic_with_error!(e, Error::TargetNotReached)
+// Stored state with the transitions that only depend on time applied:
+// funding opens at start_timestamp and after final_timestamp the project
+// is funded if it reached the soft cap, otherwise it fails
+fn current_state(e: &Env) -> ProjectState {
+    let mut state: ProjectState = read_state(e);
+    let project_info: ProjectInfo = get_project_info(e);
+    let now: u64 = e.ledger().timestamp();
+    if state == ProjectState::Created && now >= project_info.start_timestamp {
+        state = ProjectState::Funding;
     }
+    if state == ProjectState::Funding && now > project_info.final_timestamp {
+        state = match read_min_amount(e) {
+            Some(min_amount) if read_total_supply(e) >= min_amount => ProjectState::Funded,
+            _ => ProjectState::Refunding,
+        };
+    }
+    state
 }
 
-fn require_nft_collateral(e: &Env) {
+// Amount the payback of a tranche is computed on, the raised
+// amount once disbursed and the target before that
+fn tranche_principal(e: &Env, tranche: u32) -> i128 {
+    read_tranche_raised(e, tranche).unwrap_or(read_tranche(e, tranche).target_amount)
+}
+
+fn tranche_payback(e: &Env, tranche: u32) -> i128 {
+    let reward_rate: i128 = read_tranche(e, tranche).reward_rate;
+    mul_div(e, tranche_principal(e, tranche), REWARD_DENOM + reward_rate, REWARD_DENOM, Rounding::Up)
+}
+
+fn principal(e: &Env) -> i128 {
+    (0..number_of_tranches(e)).map(|tranche| tranche_principal(e, tranche)).sum()
+}
+
+fn total_payback(e: &Env) -> i128 {
+    (0..number_of_tranches(e)).map(|tranche| tranche_payback(e, tranche)).sum()
+}
+
+// Records the part of a return allocated to a tranche, `principal_paid`
+// is the part of it that repays the principal
+fn repay_tranche(e: &Env, tranche: u32, amount: i128, principal_paid: i128, protocol_fee: i128, fully_repaid: bool) {
+    add_tranche_repaid(e, tranche, amount);
+    add_tranche_return(e, tranche, amount - protocol_fee);
+    distribute_return(e, tranche, amount - protocol_fee);
+    let principal_repaid: i128 = if fully_repaid {
+        tranche_principal(e, tranche)
+    } else {
+        read_tranche_principal_repaid(e, tranche) + principal_paid
+    };
+    write_tranche_principal_repaid(e, tranche, principal_repaid);
+}
+
+// LP tokens of a tranche are burned by the claims once its principal is repaid
+fn is_tranche_repaid(e: &Env, tranche: u32) -> bool {
+    read_tranche_principal_repaid(e, tranche) >= tranche_principal(e, tranche)
+}
+
+// Queued actions are authorized by the role that could call them directly
+fn require_action_authority(e: &Env, action: &AdminAction, caller: &Address) {
+    match action {
+        AdminAction::SetProjectField(_) => require_role(e, Role::Operator, caller),
+        AdminAction::RescueTokens(_, _, _) => require_role(e, Role::Treasurer, caller),
+        AdminAction::Upgrade(_)
+        | AdminAction::SetTimelockDelay(_)
+        | AdminAction::Mint(_, _)
+        | AdminAction::Burn(_, _) => require_admin(e, caller),
+    }
+}
+
+fn validate_project_info(e: &Env, project_info: &ProjectInfo) {
+    if project_info.target_amount <= 0 {
+        panic_with_error!(e, ProjectInfoError::InvalidTargetAmount)
+    }
+    if project_info.start_timestamp >= project_info.final_timestamp {
+        panic_with_error!(e, ProjectInfoError::InvalidTimestamps)
+    }
+    if project_info.reward_rate < 0 || project_info.reward_rate > MAX_REWARD_RATE {
+        panic_with_error!(e, ProjectInfoError::InvalidRewardRate)
+    }
+    if project_info.min_lend_amount < 0
+        || project_info
+            .max_lend_amount
+            .is_some_and(|max| max < project_info.min_lend_amount)
+        || project_info
+            .max_concentration
+            .is_some_and(|max| max <= 0 || max > REWARD_DENOM)
+    {
+        panic_with_error!(e, Error::InvalidLendLimits)
+    }
+}
+
+// Every field is fixed once anyone has lent, the start and the lend
+// limits once the funding started and the borrower and collateral once
+// the collateral is held. Later changes need a passed proposal.
+fn require_field_editable(e: &Env, field: &ProjectField) {
+    let state: ProjectState = sync_state(e);
+    let lent: bool = read_total_supply(e) > 0
+        || !matches!(state, ProjectState::Created | ProjectState::Funding);
+    let frozen: bool = match field {
+        ProjectField::StartTimestamp(_)
+        | ProjectField::MinLendAmount(_)
+        | ProjectField::MaxLendAmount(_)
+        | ProjectField::MaxConcentration(_) => lent || state != ProjectState::Created,
+        ProjectField::Borrower(_)
+        | ProjectField::CollateralNftAddress(_)
+        | ProjectField::CollateralId(_) => {
+            lent || read_collateral_depositor(e).is_some() || has_nft_collateral(e)
+        }
+        _ => lent,
+    };
+    if frozen {
+        panic_with_error!(e, ProjectInfoError::FieldFrozen)
+    }
+}
+
+// Sets a single field on the project info and returns its old value
+fn replace_project_field(project_info: &mut ProjectInfo, field: ProjectField) -> ProjectField {
+    match field {
+        ProjectField::Borrower(borrower) => ProjectField::Borrower(core::mem::replace(
+            &mut project_info.borrower,
+            borrower,
+        )),
+        ProjectField::LendTokenAddress(address) => ProjectField::LendTokenAddress(
+            core::mem::replace(&mut project_info.lend_token_address, address),
+        ),
+        ProjectField::CollateralNftAddress(address) => ProjectField::CollateralNftAddress(
+            core::mem::replace(&mut project_info.collateral_nft_address, address),
+        ),
+        ProjectField::CollateralId(id) => {
+            ProjectField::CollateralId(core::mem::replace(&mut project_info.collateral_id, id))
+        }
+        ProjectField::TargetAmount(amount) => ProjectField::TargetAmount(core::mem::replace(
+            &mut project_info.target_amount,
+            amount,
+        )),
+        ProjectField::StartTimestamp(timestamp) => ProjectField::StartTimestamp(
+            core::mem::replace(&mut project_info.start_timestamp, timestamp),
+        ),
+        ProjectField::FinalTimestamp(timestamp) => ProjectField::FinalTimestamp(
+            core::mem::replace(&mut project_info.final_timestamp, timestamp),
+        ),
+        ProjectField::RewardRate(rate) => {
+            ProjectField::RewardRate(core::mem::replace(&mut project_info.reward_rate, rate))
+        }
+        ProjectField::TreasuryAddress(address) => ProjectField::TreasuryAddress(
+            core::mem::replace(&mut project_info.treasury_address, address),
+        ),
+        ProjectField::MinLendAmount(amount) => ProjectField::MinLendAmount(core::mem::replace(
+            &mut project_info.min_lend_amount,
+            amount,
+        )),
+        ProjectField::MaxLendAmount(amount) => ProjectField::MaxLendAmount(core::mem::replace(
+            &mut project_info.max_lend_amount,
+            amount,
+        )),
+        ProjectField::MaxConcentration(concentration) => ProjectField::MaxConcentration(
+            core::mem::replace(&mut project_info.max_concentration, concentration),
+        ),
+    }
+}
+
+// Checks of a changed field against the rest of the project setup
+fn validate_project_field(e: &Env, project_info: &ProjectInfo, field: &ProjectField) {
+    if let ProjectField::FinalTimestamp(final_timestamp) = *field {
+        if final_timestamp <= e.ledger().timestamp() {
+            panic_with_error!(e, ProjectInfoError::InvalidTimestamps)
+        }
+        // the installments are due after the end of the funding
+        if read_schedule(e)
+            .first()
+            .is_some_and(|installment| installment.due_timestamp <= final_timestamp)
+        {
+            panic_with_error!(e, Error::InvalidSchedule)
+        }
+    }
+    // tranches, schedules and the soft cap were set up for the old amounts
+    if let ProjectField::TargetAmount(_) | ProjectField::RewardRate(_) = field {
+        if e.storage().persistent().has(&DataKey::Tranches) {
+            panic_with_error!(e, Error::InvalidTranches)
+        }
+        if !read_schedule(e).is_empty() {
+            panic_with_error!(e, Error::InvalidSchedule)
+        }
+        if read_min_amount(e).is_some_and(|min_amount| min_amount > project_info.target_amount) {
+            panic_with_error!(e, Error::InvalidSoftCap)
+        }
+    }
+}
+
+// Writes a single field and publishes its old and new value
+fn write_project_field(e: &Env, field: ProjectField) {
+    write_project_fields(e, vec![e, field]);
+}
+
+// Writes fields that have to change together, the project info is
+// validated once all of them are set
+fn write_project_fields(e: &Env, fields: Vec<ProjectField>) {
+    let mut project_info: ProjectInfo = get_project_info(e);
+    let mut changes: Vec<(ProjectField, ProjectField)> = Vec::new(e);
+    for field in fields.iter() {
+        let old: ProjectField = replace_project_field(&mut project_info, field.clone());
+        changes.push_back((old, field));
+    }
+
+    validate_project_info(e, &project_info);
+    for field in fields.iter() {
+        validate_project_field(e, &project_info, &field);
+    }
+
+    e.storage()
+        .persistent()
+        .set(&DataKey::ProjectInfo, &project_info);
+    for (old, field) in changes.iter() {
+        e.events()
+            .publish((Symbol::new(e, "project_field_changed"),), (old, field));
+    }
+}
+
+// Lend tokens the contract owes to lenders: the pledged principal until it is
+// disbursed or refunded and the returns current and former holders can still
+// claim. Protocol fees are sent to the treasury with every return.
+fn lend_token_obligations(e: &Env) -> i128 {
+    let principal: i128 = if is_disbursed(e) {
+        0
+    } else {
+        read_total_supply(e)
+    };
+    let unclaimed: i128 = (0..number_of_tranches(e))
+        .map(|tranche| read_unclaimed_return(e, tranche))
+        .sum();
+    principal + unclaimed
+}
+
+// Lend tokens held above the obligations, e.g. sent to the contract by mistake
+fn lend_token_surplus(e: &Env) -> i128 {
+    let token: Address = get_project_info(e).lend_token_address;
+    let balance: i128 = token::Client::new(e, &token).balance(&e.current_contract_address());
+    (balance - lend_token_obligations(e)).max(0)
+}
+
+// Any token but the lend token can be rescued in full
+fn rescue_tokens(e: &Env, token_address: Address, to: Address, amount: i128) {
+    check_nonnegative_amount(e, amount);
+    if e.storage().persistent().has(&DataKey::ProjectInfo)
+        && token_address == get_project_info(e).lend_token_address
+        && amount > lend_token_surplus(e)
+    {
+        panic_with_error!(e, Error::InsufficientSurplus)
+    }
+
+    let token_client: token::TokenClient<'_> = token::Client::new(e, &token_address);
+    token_client.transfer(&e.current_contract_address(), &to, &amount);
+
+    e.events()
+        .publish((Symbol::new(e, "rescue_tokens"), token_address, to), amount);
+}
+
+fn upgrade(e: &Env, new_wasm_hash: BytesN<32>) {
+    e.deployer()
+        .update_current_contract_wasm(new_wasm_hash.clone());
+
+    e.events()
+        .publish((Symbol::new(e, "upgrade"),), new_wasm_hash);
+}
+
+fn set_timelock_delay(e: &Env, delay: u64) {
+    write_timelock_delay(e, delay);
+
+    e.events()
+        .publish((Symbol::new(e, "timelock_delay_changed"),), delay);
+}
+
+fn admin_mint(e: &Env, to: Address, amount: i128) {
+    check_nonnegative_amount(e, amount);
+    let tranche: u32 = single_tranche(e);
+    _mint(e.clone(), tranche, to, amount);
+}
+
+fn admin_burn(e: &Env, from: Address, amount: i128) {
+    check_nonnegative_amount(e, amount);
+    let tranche: u32 = single_tranche(e);
+    _burn(e.clone(), tranche, from, amount);
+}
+
+// Plain LP transfers, mints and burns are only possible with a single tranche
+fn single_tranche(e: &Env) -> u32 {
+    if number_of_tranches(e) > 1 {
+        panic_with_error!(e, Error::TrancheRequired)
+    }
+    0
+}
+
+// Writes the time based transitions, entrypoints call it before checking the state
+fn sync_state(e: &Env) -> ProjectState {
+    let state: ProjectState = current_state(e);
+    if read_state(e) == ProjectState::Created && state != ProjectState::Created {
+        transition(e, ProjectState::Funding);
+    }
+    if read_state(e) != state {
+        transition(e, state);
+    }
+    state
+}
+
+fn is_disbursed(e: &Env) -> bool {
+    e.storage().persistent().has(&DataKey::DisbursedAt)
+}
+
+fn has_nft_collateral(e: &Env) -> bool {
     let collateral_nft_address: Address = get_project_info(e).collateral_nft_address;
     let collateral_id: u128 = get_project_info(e).collateral_id;
-    // owner_of() &e.current_contract_address()
+    // a wrong collateral address must not trap the queries and the field
+    // updates that could fix it, a failed call counts as no collateral
     let nft_client = contract_nft::Client::new(&e, &collateral_nft_address);
-    let nft_has_owner: bool = nft_client.has_owner(&collateral_id);
+    let nft_has_owner: bool = matches!(nft_client.try_has_owner(&collateral_id), Ok(Ok(true)));
     if !nft_has_owner {
-        panic_with_error!(e, Error::NoCollateral)
+        return false;
     }
-    let nft_owner: Address = nft_client.owner_of(&collateral_id);
-    if nft_owner != e.current_contract_address() {
+    match nft_client.try_owner_of(&collateral_id) {
+        Ok(Ok(nft_owner)) => nft_owner == e.current_contract_address(),
+        _ => false,
+    }
+}
+
+
+fn require_nft_collateral(e: &Env) {
+    if !has_nft_collateral(e) {
         panic_with_error!(e, Error::NoCollateral)
     }
 }
 
+fn transfer_collateral(e: &Env, to: &Address) {
+    let collateral_nft_address: Address = get_project_info(e).collateral_nft_address;
+    let collateral_id: u128 = get_project_info(e).collateral_id;
+    let nft_client = contract_nft::Client::new(e, &collateral_nft_address);
+    nft_client.transfer(&e.current_contract_address(), to, &collateral_id);
+}
+
+// Sends the collateral back to whoever deposited it, NFTs that were
+// moved in outside of deposit_collateral go to the borrower
+fn release_collateral_to_depositor(e: &Env) {
+    let project_info: ProjectInfo = get_project_info(e);
+    let to: Address = read_collateral_depositor(e).unwrap_or(project_info.borrower);
+    transfer_collateral(e, &to);
+    write_collateral_outcome(e, CollateralStatus::Released);
+
+    e.events().publish(
+        (Symbol::new(e, "collateral_released"), to),
+        project_info.collateral_id,
+    );
+}
+
+// Everything returned by the borrower so far, including protocol fees
+fn total_repaid(e: &Env) -> i128 {
+    let total_return: i128 = e.storage().persistent().get(&DataKey::TotalReturn).unwrap_or(0);
+    let total_fee: i128 = e.storage().persistent().get(&DataKey::FeeAccumulated).unwrap_or(0);
+    total_return + total_fee
+}
+
+// Due timestamp of the last installment, or final_timestamp
+// for projects without a repayment schedule
+fn maturity_timestamp(e: &Env) -> u64 {
+    match read_schedule(e).last() {
+        Some(installment) => installment.due_timestamp,
+        None => get_project_info(e).final_timestamp,
+    }
+}
+
+// True if an installment or the whole loan is unpaid after its grace period.
+// Defaults are disabled until the admin sets the grace periods.
+fn is_payment_late(e: &Env) -> bool {
+    let config: DefaultConfig = match read_default_config(e) {
+        Some(config) => config,
+        None => return false,
+    };
+    if EnerDAOToken::borrower_to_payback(e) <= 0 {
+        return false;
+    }
+
+    let now: u64 = e.ledger().timestamp();
+    if now > maturity_timestamp(e).saturating_add(config.maturity_grace_period) {
+        return true;
+    }
+    installment_infos(e, total_repaid(e)).iter().any(|installment| {
+        installment.status == InstallmentStatus::Overdue
+            && now > installment.due_timestamp.saturating_add(config.installment_grace_period)
+    })
+}
+
 fn read_total_supply(e: &Env) -> i128 {
     let key = DataKey::TotalSupply;
     let total_supply: i128 = e.storage().persistent().get(&key).unwrap_or(0);
@@ -90,6 +504,7 @@ fn write_total_supply(e: &Env, val: i128) {
     e.storage()
         .persistent()
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    write_supply_checkpoint(e, val);
 }
 
 fn add_total_supply(e: &Env, val: i128) {
@@ -104,25 +519,32 @@ fn sub_total_supply(e: &Env, val: i128) {
     write_total_supply(e, total_supply);
 }
 
-fn _mint(e: Env, to: Address, amount: i128) {
+fn _mint(e: Env, tranche: u32, to: Address, amount: i128) {
     let admin = read_administrator(&e);
 
     e.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 
+    settle(&e, tranche, to.clone());
     receive_balance(&e, to.clone(), amount);
+    receive_tranche_balance(&e, tranche, to.clone(), amount);
     add_total_supply(&e, amount);
+    add_tranche_supply(&e, tranche, amount);
     TokenUtils::new(&e).events().mint(admin, to, amount);
 }
 
-fn _burn(e: Env, from: Address, amount: i128) {
+fn _burn(e: Env, tranche: u32, from: Address, amount: i128) {
     e.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 
+    settle(&e, tranche, from.clone());
     spend_balance(&e, from.clone(), amount);
+    spend_tranche_balance(&e, tranche, from.clone(), amount);
     sub_total_supply(&e, amount);
+    add_tranche_supply(&e, tranche, -amount);
+    _remove_lender(e.clone(), from.clone());
     TokenUtils::new(&e).events().burn(from.clone(), amount);
 }
 
@@ -140,9 +562,82 @@ fn write_number_of_lenders(e: &Env, val: u128) {
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
 }
 
+// Lend tokens paid out to the lender by claims
+fn read_claimed(e: &Env, lender: Address) -> i128 {
+    let key = DataKey::Claimed(lender);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn add_claimed(e: &Env, lender: Address, amount: i128) {
+    let key = DataKey::Claimed(lender.clone());
+    e.storage().persistent().set(&key, &(read_claimed(e, lender) + amount));
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+fn read_first_lend_at(e: &Env, lender: Address) -> Option<u64> {
+    let key = DataKey::FirstLendAt(lender);
+    e.storage().persistent().get(&key)
+}
+
+fn write_first_lend_at(e: &Env, lender: Address) {
+    let key = DataKey::FirstLendAt(lender);
+    if !e.storage().persistent().has(&key) {
+        e.storage().persistent().set(&key, &e.ledger().timestamp());
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+}
+
+// Position over all tranches. LP tokens are only burned once
+// a tranche is repaid, so this is the LP balance.
+fn lender_position(e: &Env, lender: &Address) -> i128 {
+    read_balance(e, lender.clone())
+}
+
+// Checks the per-lender limits against the position after a lend or transfer.
+// A lend that fills the rest of the target is allowed below the minimum.
+fn check_lend_limits(e: &Env, position: i128, fills_target: bool) -> Result<(), Error> {
+    let project_info: ProjectInfo = get_project_info(e);
+    if position < project_info.min_lend_amount && !fills_target {
+        return Err(Error::LendBelowMinimum);
+    }
+    if let Some(max_lend_amount) = project_info.max_lend_amount {
+        if position > max_lend_amount {
+            return Err(Error::LendAboveMaximum);
+        }
+    }
+    if let Some(max_concentration) = project_info.max_concentration {
+        let max_position: i128 = mul_div(
+            e,
+            project_info.target_amount,
+            max_concentration,
+            REWARD_DENOM,
+            Rounding::Down,
+        );
+        if position > max_position {
+            return Err(Error::ConcentrationExceeded);
+        }
+    }
+    Ok(())
+}
+
+fn check_transfer_limits(e: &Env, from: &Address, to: &Address, amount: i128) {
+    // no limits before the project is set up
+    if from == to || amount == 0 || !e.storage().persistent().has(&DataKey::ProjectInfo) {
+        return;
+    }
+    check(e, check_lend_limits(e, lender_position(e, to) + amount, false));
+}
+
 // internal function that records index of the lender
-// if this is a new lender
+// if this is a new lender with a balance
 fn _add_lender(e: Env, lender: Address) {
+    if read_balance(&e, lender.clone()) <= 0 {
+        return;
+    }
     let mut number_of_lenders: u128 = read_number_of_lenders(&e);
     let mut lender_index: u128 = e
         .storage()
@@ -163,28 +658,214 @@ fn _add_lender(e: Env, lender: Address) {
     }
 }
 
-fn transfer_claimed(e: &Env, from: &Address, to: &Address, amount: i128) {
-    
-    let key_claimed_from: DataKey = DataKey::ClaimedBalance(from.clone());
-    let mut claimed_from: i128 = e.storage().persistent().get(&key_claimed_from).unwrap_or(0);
-    let key_claimed_to: DataKey = DataKey::ClaimedBalance(to.clone());
-    let mut claimed_to: i128 = e.storage().persistent().get(&key_claimed_to).unwrap_or(0);
-
-    let balance_from: i128 = read_balance(&e, from.clone());
-
-    // splitting claimed balance proportionally to the amount transferred
-    if claimed_from > 0 {
-        claimed_to += claimed_from * amount / balance_from;
-        claimed_from -= claimed_from * amount / balance_from;
-        e.storage().persistent().set(&key_claimed_from, &claimed_from);
-        e.storage().persistent().set(&key_claimed_to, &claimed_to);
+// internal function that removes a lender without balance,
+// the last lender takes its index
+fn _remove_lender(e: Env, lender: Address) {
+    if read_balance(&e, lender.clone()) > 0 {
+        return;
+    }
+    let lender_index: u128 = match e
+        .storage()
+        .persistent()
+        .get(&DataKey::LenderIndex(lender.clone()))
+    {
+        Some(lender_index) => lender_index,
+        None => return,
+    };
+
+    let number_of_lenders: u128 = read_number_of_lenders(&e);
+    if lender_index != number_of_lenders {
+        let last_lender: Address = e
+            .storage()
+            .persistent()
+            .get(&DataKey::LenderAddress(number_of_lenders))
+            .unwrap();
         e.storage()
             .persistent()
-            .extend_ttl(&key_claimed_from, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+            .set(&DataKey::LenderIndex(last_lender.clone()), &lender_index);
         e.storage()
             .persistent()
-            .extend_ttl(&key_claimed_to, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
-    } 
+            .set(&DataKey::LenderAddress(lender_index), &last_lender);
+    }
+    e.storage()
+        .persistent()
+        .remove(&DataKey::LenderAddress(number_of_lenders));
+    e.storage()
+        .persistent()
+        .remove(&DataKey::LenderIndex(lender));
+    write_number_of_lenders(&e, number_of_lenders - 1);
+}
+
+// Returns earned so far stay with the sender, the recipient
+// only earns on the tokens from the next return on
+fn _transfer(e: &Env, tranche: u32, from: Address, to: Address, amount: i128) {
+    require_not_paused(e, PausableFunction::Transfer);
+    // nothing moves, the recipient is not registered as a lender
+    if amount == 0 {
+        return;
+    }
+    require_kyc(e, &to);
+    check_transfer_limits(e, &from, &to, amount);
+    settle(e, tranche, from.clone());
+    settle(e, tranche, to.clone());
+    spend_balance(e, from.clone(), amount);
+    spend_tranche_balance(e, tranche, from.clone(), amount);
+    receive_balance(e, to.clone(), amount);
+    receive_tranche_balance(e, tranche, to.clone(), amount);
+    _add_lender(e.clone(), to.clone());
+    _remove_lender(e.clone(), from.clone());
+    TokenUtils::new(e).events().transfer(from, to, amount);
+}
+
+fn check_lend(e: &Env, lender: &Address, tranche: u32, amount: i128) -> Result<(), Error> {
+    if is_paused(e, PausableFunction::Lend) {
+        return Err(Error::FunctionPaused);
+    }
+    if amount < 0 {
+        return Err(Error::OnlyPositiveValue);
+    }
+    match current_state(e) {
+        ProjectState::Funding => {}
+        ProjectState::Created => return Err(Error::NotStarted),
+        ProjectState::Funded if !is_after_final_time(e) => return Err(Error::TargetOverreached),
+        _ => return Err(Error::AlreadyFinished),
+    }
+    if !is_kyc_approved(e, lender) {
+        return Err(Error::NotKycApproved);
+    }
+
+    let tranche_target: i128 = match read_tranches(e).get(tranche) {
+        Some(tranche) => tranche.target_amount,
+        None => return Err(Error::InvalidTranche),
+    };
+    let tranche_supply: i128 = read_tranche_supply(e, tranche);
+    if tranche_supply + amount > tranche_target {
+        return Err(Error::TargetOverreached);
+    }
+    check_lend_limits(
+        e,
+        lender_position(e, lender) + amount,
+        tranche_supply + amount == tranche_target,
+    )
+}
+
+// Amount paid out by a claim and the LP tokens it burns. LP tokens
+// keep earning until the principal of the tranche is repaid.
+fn claim_amounts(e: &Env, lender: &Address, tranche: u32) -> (i128, i128) {
+    let entitled_amount: i128 =
+        EnerDAOToken::lender_available_to_claim(e.clone(), lender.clone(), tranche);
+    let burn_amount: i128 = if current_state(e) == ProjectState::Refunding {
+        entitled_amount
+    } else if is_tranche_repaid(e, tranche) {
+        read_tranche_balance(e, tranche, lender.clone())
+    } else {
+        0
+    };
+    (entitled_amount, burn_amount)
+}
+
+fn check_lender_claim(e: &Env, lender: &Address, tranche: u32) -> Result<(), Error> {
+    if is_paused(e, PausableFunction::LenderClaim) {
+        return Err(Error::FunctionPaused);
+    }
+    if tranche >= number_of_tranches(e) {
+        return Err(Error::InvalidTranche);
+    }
+    let (entitled_amount, burn_amount) = claim_amounts(e, lender, tranche);
+    if entitled_amount <= 0 && burn_amount <= 0 {
+        return Err(Error::NothingToClaim);
+    }
+    Ok(())
+}
+
+fn check_borrower_claim(e: &Env) -> Result<(), Error> {
+    if is_paused(e, PausableFunction::BorrowerClaim) {
+        return Err(Error::FunctionPaused);
+    }
+    match current_state(e) {
+        ProjectState::Funded => {}
+        _ if is_disbursed(e) => return Err(Error::AlreadyClaimed),
+        ProjectState::Created | ProjectState::Funding => return Err(Error::TargetNotReached),
+        _ => return Err(Error::InvalidProjectState),
+    }
+    if !has_nft_collateral(e) {
+        return Err(Error::NoCollateral);
+    }
+    Ok(())
+}
+
+fn check_borrower_return(e: &Env, amount: i128) -> Result<(), Error> {
+    if is_paused(e, PausableFunction::BorrowerReturn) {
+        return Err(Error::FunctionPaused);
+    }
+    if amount < 0 {
+        return Err(Error::OnlyPositiveValue);
+    }
+    match current_state(e) {
+        ProjectState::Disbursed | ProjectState::Repaying | ProjectState::Defaulted => {}
+        _ => return Err(Error::InvalidProjectState),
+    }
+    if amount > EnerDAOToken::borrower_to_payback(e) {
+        return Err(Error::ReturnOverreached);
+    }
+    Ok(())
+}
+
+fn require_valid_proposal_action(e: &Env, action: &ProposalAction) {
+    let state: ProjectState = current_state(e);
+    match action {
+        ProposalAction::ExtendDeadline(final_timestamp) => {
+            match state {
+                ProjectState::Created | ProjectState::Funding => {}
+                _ => panic_with_error!(e, Error::InvalidProjectState),
+            }
+            if *final_timestamp <= get_project_info(e).final_timestamp {
+                panic_with_error!(e, GovernanceError::InvalidProposal)
+            }
+        }
+        // the payback of tranches and schedules is fixed by their own rates
+        ProposalAction::SetRewardRate(reward_rate) => {
+            match state {
+                ProjectState::Created | ProjectState::Funding | ProjectState::Funded => {}
+                _ => panic_with_error!(e, Error::InvalidProjectState),
+            }
+            if *reward_rate < 0
+                || *reward_rate > MAX_REWARD_RATE
+                || e.storage().persistent().has(&DataKey::Tranches)
+                || !read_schedule(e).is_empty()
+            {
+                panic_with_error!(e, GovernanceError::InvalidProposal)
+            }
+        }
+        ProposalAction::ReplaceBorrower(_) | ProposalAction::SetTreasury(_) => {
+            if state == ProjectState::Closed {
+                panic_with_error!(e, Error::InvalidProjectState)
+            }
+        }
+        ProposalAction::ReleaseCollateral => {
+            if read_collateral_outcome(e).is_some() || !has_nft_collateral(e) {
+                panic_with_error!(e, Error::NoCollateral)
+            }
+        }
+    }
+}
+
+fn apply_proposal_action(e: &Env, action: ProposalAction) {
+    match action {
+        ProposalAction::ExtendDeadline(final_timestamp) => {
+            write_project_field(e, ProjectField::FinalTimestamp(final_timestamp))
+        }
+        ProposalAction::SetRewardRate(reward_rate) => {
+            write_project_field(e, ProjectField::RewardRate(reward_rate))
+        }
+        ProposalAction::ReplaceBorrower(borrower) => {
+            write_project_field(e, ProjectField::Borrower(borrower))
+        }
+        ProposalAction::SetTreasury(treasury_address) => {
+            write_project_field(e, ProjectField::TreasuryAddress(treasury_address))
+        }
+        ProposalAction::ReleaseCollateral => release_collateral_to_depositor(e),
+    }
 }
 
 #[contract]
@@ -197,12 +878,6 @@ fn move_token(env: &Env, from: &Address, to: &Address, transfer_amount: i128) {
     token_client.transfer(&from, to, &transfer_amount);
 }
 
-fn contract_balance(env: &Env) -> i128 {
-    let token: Address = get_project_info(env).lend_token_address;
-    let token_client: token::TokenClient = token::Client::new(&env, &token);
-    let balance: i128 = token_client.balance(&env.current_contract_address());
-    balance
-}
 
 #[contractimpl]
 impl EnerDAOToken {
@@ -220,6 +895,35 @@ impl EnerDAOToken {
                 symbol,
             },
         );
+        write_schema_version(&e, SCHEMA_VERSION);
+    }
+
+    // Replaces the contract code, the storage is kept as is and has to be
+    // brought to the new layout with `migrate` if its schema changed
+    pub fn upgrade(e: Env, new_wasm_hash: BytesN<32>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+        require_no_timelock(&e);
+
+        upgrade(&e, new_wasm_hash);
+    }
+
+    // Rewrites the storage written by an older code version to the current layout
+    pub fn migrate(e: Env) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        let from: u32 = migrate(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "migrate"), admin), (from, SCHEMA_VERSION));
+    }
+
+    pub fn version(e: Env) -> Version {
+        Version {
+            code_version: CODE_VERSION,
+            schema_version: read_schema_version(&e),
+        }
     }
 
     pub fn init_project(
@@ -233,10 +937,14 @@ impl EnerDAOToken {
         final_timestamp: u64,
         reward_rate: i128,
         treasury_address: Address,
+        schedule: Option<RepaymentSchedule>,
     ) {
         let admin = read_administrator(&e);
         admin.require_auth();
 
+        if e.storage().persistent().has(&DataKey::ProjectInfo) {
+            panic_with_error!(&e, ProjectInfoError::ProjectAlreadyInitialized)
+        }
         let project_info: ProjectInfo = ProjectInfo {
             borrower,
             lend_token_address,
@@ -247,167 +955,242 @@ impl EnerDAOToken {
             final_timestamp,
             reward_rate,
             treasury_address,
+            min_lend_amount: 0,
+            max_lend_amount: None,
+            max_concentration: None,
         };
+        validate_project_info(&e, &project_info);
+        if final_timestamp <= e.ledger().timestamp() {
+            panic_with_error!(&e, ProjectInfoError::InvalidTimestamps)
+        }
         let project_key: DataKey = DataKey::ProjectInfo;
         e.storage().persistent().set(&project_key, &project_info);
+
+        if let Some(schedule) = schedule {
+            let total_payback: i128 =
+                mul_div(&e, target_amount, REWARD_DENOM + reward_rate, REWARD_DENOM, Rounding::Up);
+            init_schedule(&e, schedule, total_payback, final_timestamp);
+        }
     }
 
-    pub fn lend(e: Env, lender: Address, amount: i128) {
+    pub fn lend(e: Env, lender: Address, tranche: u32, amount: i128) {
         check_nonnegative_amount(&e, amount);
         lender.require_auth();
 
-        require_start_time_reached(&e);
-        require_final_time_not_reached(&e);
-
-        let target_amount: i128 = get_project_info(&e).target_amount;
-        let total_supply: i128 = read_total_supply(&e);
-        if total_supply + amount > target_amount {
-            panic_with_error!(e, Error::TargetOverreached)
-        }
+        sync_state(&e);
+        check(&e, check_lend(&e, &lender, tranche, amount));
 
         move_token(&e, &lender, &e.current_contract_address(), amount);
-        _mint(e.clone(), lender.clone(), amount);
+        _mint(e.clone(), tranche, lender.clone(), amount);
+        write_first_lend_at(&e, lender.clone());
+
+        let target_amount: i128 = get_project_info(&e).target_amount;
         _add_lender(e.clone(), lender.clone());
 
+        if read_total_supply(&e) >= target_amount {
+            transition(&e, ProjectState::Funded);
+        }
+
         e.events()
             .publish((Symbol::new(&e, "lend"), lender.clone()), (amount));
     }
 
-    pub fn is_lender_claim_available(e: &Env) -> bool {
-        let key: DataKey = DataKey::ClaimAvailable;
-        let mut claim_available: bool = e.storage().persistent().get(&key).unwrap_or(false);
-        let target_not_reached: bool = Self::is_target_not_reached(&e);
-        if target_not_reached {
-            claim_available = true;
+    // Returns a pledge while the project is still funding,
+    // except during the lock period before final_timestamp
+    pub fn withdraw_pledge(e: Env, lender: Address, tranche: u32, amount: i128) {
+        check_nonnegative_amount(&e, amount);
+        lender.require_auth();
+        require_not_paused(&e, PausableFunction::WithdrawPledge);
+
+        if sync_state(&e) != ProjectState::Funding {
+            panic_with_error!(&e, Error::InvalidProjectState)
+        }
+        let final_timestamp: u64 = get_project_info(&e).final_timestamp;
+        let lock_period: u64 = read_pledge_lock_period(&e);
+        if e.ledger().timestamp() > final_timestamp.saturating_sub(lock_period) {
+            panic_with_error!(&e, Error::PledgeLocked)
+        }
+
+        // A partial withdrawal can not leave less than the minimum in the tranche
+        let remaining: i128 = read_tranche_balance(&e, tranche, lender.clone()) - amount;
+        if remaining > 0 && remaining < get_project_info(&e).min_lend_amount {
+            panic_with_error!(&e, Error::LendBelowMinimum)
         }
-        return claim_available;
+
+        _burn(e.clone(), tranche, lender.clone(), amount);
+        move_token(&e, &e.current_contract_address(), &lender, amount);
+
+        e.events()
+            .publish((Symbol::new(&e, "withdraw_pledge"), lender.clone()), amount);
     }
 
-    pub fn is_target_not_reached(e: &Env) -> bool {
-        let mut target_not_reached = e
-            .storage()
-            .persistent()
-            .get(&DataKey::TargetNotReached)
-            .unwrap_or(false);
-        if !target_not_reached {
-            let final_time: u64 = get_project_info(&e).final_timestamp;
-            let target_amount: i128 = get_project_info(&e).target_amount;
-            let already_claimed: bool = e
-                .storage()
-                .persistent()
-                .get(&DataKey::BorrowerClaimed)
-                .unwrap_or(false);
-            if !already_claimed
-                && e.ledger().timestamp() > final_time
-                && read_total_supply(&e) < target_amount
-            {
-                target_not_reached = true;
-            }
-        }
-        return target_not_reached;
+    pub fn project_state(e: Env) -> ProjectState {
+        current_state(&e)
     }
 
-    pub fn lender_available_to_claim(e: Env, lender: Address) -> i128 {
-        let lender_balance: i128 = read_balance(&e, lender.clone());
+    pub fn is_lender_claim_available(e: &Env) -> bool {
+        matches!(
+            current_state(e),
+            ProjectState::Repaying
+                | ProjectState::Repaid
+                | ProjectState::Defaulted
+                | ProjectState::Refunding
+        )
+    }
 
-        let target_not_reached: bool = Self::is_target_not_reached(&e);
+    pub fn is_target_not_reached(e: &Env) -> bool {
+        current_state(e) == ProjectState::Refunding
+    }
 
-        if target_not_reached {
-            return lender_balance;
-        }
+    pub fn lender_available_to_claim(e: Env, lender: Address, tranche: u32) -> i128 {
+        read_tranche(&e, tranche);
+        let lender_balance: i128 = read_tranche_balance(&e, tranche, lender.clone());
 
-        if !Self::is_lender_claim_available(&e) {
-            return 0;
+        match current_state(&e) {
+            ProjectState::Refunding => return lender_balance,
+            ProjectState::Repaying | ProjectState::Repaid | ProjectState::Defaulted => {}
+            _ => return 0,
         }
 
-        let key_claimed: DataKey = DataKey::ClaimedBalance(lender.clone());
-        let already_claimed: i128 = e.storage().persistent().get(&key_claimed).unwrap_or(0);
-
-        let key_return: DataKey = DataKey::TotalReturn;
-        let total_return: i128 = e.storage().persistent().get(&key_return).unwrap_or(0);
-        let target_amount: i128 = get_project_info(&e).target_amount;
-
-        let total_available_to_claim: i128 =
-            total_return * (lender_balance + already_claimed) / target_amount;
+        // Returns earned through the return index, never more
+        // than the part of the tranche return not paid out yet
+        let unpaid: i128 = read_tranche_return(&e, tranche) - read_tranche_paid(&e, tranche);
+        read_pending_return(&e, tranche, lender).min(unpaid).max(0)
+    }
 
-        let reward_rate: i128 = get_project_info(&e).reward_rate;
+    // Escape hatch once the claims or returns were paused for longer than the
+    // maximum pause duration. Pays what the lender could claim, the pledge before
+    // the disbursement and the pending returns, cut pro rata if the contract holds
+    // less than it owes. The LP tokens are burned for the pledge only, after the
+    // disbursement the lender keeps them to share in the later returns.
+    pub fn emergency_withdraw(e: Env, lender: Address) {
+        lender.require_auth();
 
-        let available_to_claim = total_available_to_claim
-            - already_claimed * (reward_rate*(REWARD_DENOM - PROTOCOL_FEE)/REWARD_DENOM + REWARD_DENOM) / REWARD_DENOM;
+        sync_state(&e);
+        if !Self::is_emergency(e.clone()) {
+            panic_with_error!(&e, Error::NoEmergency)
+        }
+        let disbursed: bool = is_disbursed(&e);
+        let principal: i128 = if disbursed {
+            0
+        } else {
+            read_balance(&e, lender.clone())
+        };
+        let mut owed: i128 = principal;
+        for tranche in 0..number_of_tranches(&e) {
+            owed += read_pending_return(&e, tranche, lender.clone());
+        }
+        if owed <= 0 {
+            panic_with_error!(&e, Error::NothingToClaim)
+        }
 
-        let contract_balance: i128 = contract_balance(&e);
-        // Rounding issue
-        if available_to_claim > contract_balance {
-            return contract_balance;
-        } else if available_to_claim == 1{
-            return 0;
+        let token: Address = get_project_info(&e).lend_token_address;
+        let contract_balance: i128 =
+            token::Client::new(&e, &token).balance(&e.current_contract_address());
+        let obligations: i128 = lend_token_obligations(&e);
+        let amount: i128 = if contract_balance >= obligations {
+            owed
         } else {
-            return available_to_claim;
+            mul_div(&e, owed, contract_balance, obligations, Rounding::Down)
+        };
+
+        // the pending returns are settled for good, the paid part of them
+        // counts as paid out to the tranche
+        for tranche in 0..number_of_tranches(&e) {
+            settle(&e, tranche, lender.clone());
+            let pending: i128 = read_pending_return(&e, tranche, lender.clone());
+            if pending > 0 {
+                spend_pending_return(&e, tranche, lender.clone(), pending);
+                add_tranche_paid(&e, tranche, mul_div(&e, pending, amount, owed, Rounding::Down));
+            }
+            let tranche_balance: i128 = read_tranche_balance(&e, tranche, lender.clone());
+            if !disbursed && tranche_balance > 0 {
+                _burn(e.clone(), tranche, lender.clone(), tranche_balance);
+            }
         }
+        move_token(&e, &e.current_contract_address(), &lender, amount);
+        add_claimed(&e, lender.clone(), amount);
+
+        e.events()
+            .publish((Symbol::new(&e, "emergency_withdraw"), lender), amount);
     }
 
-    pub fn lender_claim(e: Env, lender: Address) {
+    pub fn lender_claim(e: Env, lender: Address, tranche: u32) {
         lender.require_auth();
 
-        let entitled_amount: i128 = Self::lender_available_to_claim(e.clone(), lender.clone());
-
-        if entitled_amount <= 0 {
-            panic_with_error!(e, Error::NothingToClaim)
-        }
-        let reward_rate: i128 = get_project_info(&e).reward_rate;
+        let state: ProjectState = sync_state(&e);
 
-        let target_not_reached: bool = Self::is_target_not_reached(&e);
+        check(&e, check_lender_claim(&e, &lender, tranche));
+        let (entitled_amount, burn_amount) = claim_amounts(&e, &lender, tranche);
 
-        let mut burn_amount: i128;
-        if target_not_reached {
-            burn_amount = entitled_amount;
-        } else {
-            burn_amount = entitled_amount * REWARD_DENOM / (REWARD_DENOM + reward_rate*(REWARD_DENOM - PROTOCOL_FEE)/REWARD_DENOM);
+        if burn_amount > 0 {
+            _burn(e.clone(), tranche, lender.clone(), burn_amount);
         }
 
-        let lender_balance: i128 = read_balance(&e, lender.clone());
-
-        if  burn_amount + 100 >= lender_balance {
-            burn_amount = lender_balance;
+        if entitled_amount > 0 {
+            move_token(&e, &e.current_contract_address(), &lender, entitled_amount);
+            add_claimed(&e, lender.clone(), entitled_amount);
+        }
+        if state != ProjectState::Refunding {
+            settle(&e, tranche, lender.clone());
+            spend_pending_return(&e, tranche, lender.clone(), entitled_amount);
+            add_tranche_paid(&e, tranche, entitled_amount);
         }
-
-        _burn(e.clone(), lender.clone(), burn_amount);
-
-        move_token(&e, &e.current_contract_address(), &lender, entitled_amount);
-
-        let key_claimed: DataKey = DataKey::ClaimedBalance(lender.clone());
-        let mut already_claimed: i128 = e.storage().persistent().get(&key_claimed).unwrap_or(0);
-        already_claimed += burn_amount;
-        e.storage().persistent().set(&key_claimed, &already_claimed);
 
         e.events().publish(
             (Symbol::new(&e, "lender_claim"), lender.clone()),
-            (entitled_amount),
+            entitled_amount,
         );
+
+        // Whatever the rounding left of a repaid tranche is dust
+        if state != ProjectState::Refunding
+            && is_tranche_repaid(&e, tranche)
+            && read_tranche_supply(&e, tranche) == 0
+        {
+            book_dust(&e, tranche);
+        }
+
+        // Former holders that transferred all their LP tokens
+        // away can still have returns to claim
+        if (state == ProjectState::Repaid || state == ProjectState::Refunding)
+            && read_total_supply(&e) == 0
+            && !has_pending_returns(&e)
+        {
+            transition(&e, ProjectState::Closed);
+        }
     }
 
     pub fn borrower_claim(e: Env) {
         let borrower: Address = get_project_info(&e).borrower;
         borrower.require_auth();
 
-        let already_claimed: bool = e
-            .storage()
-            .persistent()
-            .get(&DataKey::BorrowerClaimed)
-            .unwrap_or(false);
-        if already_claimed {
-            panic_with_error!(&e, Error::AlreadyClaimed)
-        }
-
-        require_target_amount_reached(&e);
+        sync_state(&e);
         // require_final_time_reached(&e);
-        require_nft_collateral(&e);
+        check(&e, check_borrower_claim(&e));
 
+        transition(&e, ProjectState::Disbursed);
         e.storage()
             .persistent()
-            .set(&DataKey::BorrowerClaimed, &true);
+            .set(&DataKey::DisbursedAt, &e.ledger().timestamp());
 
+        // Below the target only the raised amount is owed
         let amount: i128 = read_total_supply(&e);
+        for tranche in 0..number_of_tranches(&e) {
+            write_tranche_raised(&e, tranche, read_tranche_supply(&e, tranche));
+        }
+        if amount < get_project_info(&e).target_amount {
+            scale_schedule(&e, total_payback(&e));
+        }
+        if is_accruing(&e) {
+            write_loan(
+                &e,
+                &Loan {
+                    principal: amount,
+                    interest: 0,
+                    accrued_at: e.ledger().timestamp(),
+                },
+            );
+        }
         move_token(&e, &e.current_contract_address(), &borrower, amount);
 
         e.events().publish(
@@ -416,43 +1199,72 @@ impl EnerDAOToken {
         );
     }
 
-    pub fn borrower_claim_status(e: &Env) -> String {
-        let already_claimed: bool = e
-            .storage()
-            .persistent()
-            .get(&DataKey::BorrowerClaimed)
-            .unwrap_or(false);
-        if already_claimed {
-            return String::from_str(e, "AlreadyClaimed");
+    // Dry runs, what `borrower_claim`, `lend`, `lender_claim` or `borrower_return`
+    // would fail with right now, `Available` if the call would go through.
+    // Returning the Error itself would fail the query. Failing transfers of
+    // the lend token are not covered.
+    pub fn borrower_claim_status(e: &Env) -> BorrowerClaimStatus {
+        match check_borrower_claim(e) {
+            Ok(()) => BorrowerClaimStatus::Available,
+            Err(Error::FunctionPaused) => BorrowerClaimStatus::Paused,
+            Err(Error::AlreadyClaimed) => BorrowerClaimStatus::AlreadyClaimed,
+            Err(Error::TargetNotReached) => BorrowerClaimStatus::TargetNotReached,
+            Err(Error::NoCollateral) => BorrowerClaimStatus::NoCollateral,
+            Err(Error::InvalidProjectState) => BorrowerClaimStatus::InvalidState,
+            Err(error) => panic_with_error!(e, error),
         }
+    }
 
-        let project_info: ProjectInfo = get_project_info(e);
-        let target_amount: i128 = project_info.target_amount;
-        // let final_time: u64 = project_info.final_timestamp;
-        // if e.ledger().timestamp() <= final_time {
-        //     return String::from_str(e, "NotFinished");
-        // }
-        if read_total_supply(&e) < target_amount {
-            return String::from_str(e, "TargetNotReached");
+    pub fn lend_status(e: Env, lender: Address, tranche: u32, amount: i128) -> LendStatus {
+        match check_lend(&e, &lender, tranche, amount) {
+            Ok(()) => LendStatus::Available,
+            Err(Error::FunctionPaused) => LendStatus::Paused,
+            Err(Error::OnlyPositiveValue) => LendStatus::InvalidAmount,
+            Err(Error::NotStarted) => LendStatus::NotStarted,
+            Err(Error::AlreadyFinished) => LendStatus::AlreadyFinished,
+            Err(Error::NotKycApproved) => LendStatus::NotKycApproved,
+            Err(Error::InvalidTranche) => LendStatus::InvalidTranche,
+            Err(Error::TargetOverreached) => LendStatus::TargetOverreached,
+            Err(Error::LendBelowMinimum) => LendStatus::BelowMinimum,
+            Err(Error::LendAboveMaximum) => LendStatus::AboveMaximum,
+            Err(Error::ConcentrationExceeded) => LendStatus::ConcentrationExceeded,
+            Err(error) => panic_with_error!(&e, error),
         }
-        let collateral_nft_address: Address = project_info.collateral_nft_address;
-        let collateral_id: u128 = project_info.collateral_id;
-        let nft_client = contract_nft::Client::new(&e, &collateral_nft_address);
-        let nft_has_owner: bool = nft_client.has_owner(&collateral_id);
-        if !nft_has_owner {
-            return String::from_str(e, "NoCollateral");
+    }
+
+    pub fn lender_claim_status(e: Env, lender: Address, tranche: u32) -> LenderClaimStatus {
+        match check_lender_claim(&e, &lender, tranche) {
+            Ok(()) => LenderClaimStatus::Available,
+            Err(Error::FunctionPaused) => LenderClaimStatus::Paused,
+            Err(Error::InvalidTranche) => LenderClaimStatus::InvalidTranche,
+            Err(Error::NothingToClaim) => LenderClaimStatus::NothingToClaim,
+            Err(error) => panic_with_error!(&e, error),
         }
-        let nft_owner: Address = nft_client.owner_of(&collateral_id);
-        if nft_owner != e.current_contract_address() {
-            return String::from_str(e, "NoCollateral");
+    }
+
+    pub fn borrower_return_status(e: Env, amount: i128) -> BorrowerReturnStatus {
+        match check_borrower_return(&e, amount) {
+            Ok(()) => BorrowerReturnStatus::Available,
+            Err(Error::FunctionPaused) => BorrowerReturnStatus::Paused,
+            Err(Error::OnlyPositiveValue) => BorrowerReturnStatus::InvalidAmount,
+            Err(Error::InvalidProjectState) => BorrowerReturnStatus::InvalidState,
+            Err(Error::ReturnOverreached) => BorrowerReturnStatus::ReturnOverreached,
+            Err(error) => panic_with_error!(&e, error),
         }
-        return String::from_str(e, "Available");
     }
 
     
+    // With accruing interest this is the amount owed as of the current ledger timestamp
     pub fn borrower_to_payback(e: &Env) -> i128 {
-        let project_info: ProjectInfo = get_project_info(e);
-        let reward_rate: i128 = project_info.reward_rate;
+        if is_accruing(e) {
+            return match read_loan(e) {
+                Some(loan) => {
+                    let loan: Loan = accrue(e, &loan);
+                    loan.principal + loan.interest
+                }
+                None => principal(e),
+            };
+        }
 
         let key_return: DataKey = DataKey::TotalReturn;
         let total_return: i128 = e.storage().persistent().get(&key_return).unwrap_or(0);
@@ -460,24 +1272,79 @@ impl EnerDAOToken {
         let key_fee: DataKey = DataKey::FeeAccumulated;
         let total_fee: i128 = e.storage().persistent().get(&key_fee).unwrap_or(0);
 
-        let payback: i128 =  project_info.target_amount * (REWARD_DENOM + reward_rate) / REWARD_DENOM - (total_return + total_fee);
+        let payback: i128 = total_payback(e) - (total_return + total_fee);
 
         return payback;
     }
 
+    pub fn schedule(e: Env) -> Vec<InstallmentInfo> {
+        installment_infos(&e, total_repaid(&e))
+    }
+
+    // First installment that is not paid in full, if any
+    pub fn next_installment(e: Env) -> Option<InstallmentInfo> {
+        Self::schedule(e)
+            .iter()
+            .find(|installment| installment.status != InstallmentStatus::Paid)
+    }
+
+    // Unpaid part of the installments that are past their due timestamp
+    pub fn overdue_amount(e: Env) -> i128 {
+        let mut overdue: i128 = 0;
+        for installment in Self::schedule(e).iter() {
+            if installment.status == InstallmentStatus::Overdue {
+                overdue += installment.amount - installment.paid;
+            }
+        }
+        overdue
+    }
+
     pub fn borrower_return(e: Env, borrower: Address, amount: i128) {
         borrower.require_auth();
 
         // require_final_time_reached(&e);
+        let state: ProjectState = sync_state(&e);
+        check(&e, check_borrower_return(&e, amount));
 
         move_token(&e, &borrower, &e.current_contract_address(), amount);
 
         // Calculation of protocol fee
         let project_info: ProjectInfo = get_project_info(&e);
-        let reward_rate: i128 = project_info.reward_rate;
-        let base_return: i128 = amount * REWARD_DENOM
-            / (REWARD_DENOM + reward_rate);
-        let protocol_fee: i128 = base_return * (reward_rate * PROTOCOL_FEE / REWARD_DENOM) / REWARD_DENOM;
+        let mut protocol_fee: i128 = 0;
+        if is_accruing(&e) {
+            // accruing projects have a single tranche
+            let (principal_paid, interest_paid) = repay_loan(&e, amount);
+            protocol_fee = mul_div(&e, interest_paid, read_protocol_fee(&e), REWARD_DENOM, Rounding::Down);
+            let fully_repaid: bool = Self::borrower_to_payback(&e) == 0;
+            repay_tranche(&e, 0, amount, principal_paid, protocol_fee, fully_repaid);
+        } else {
+            // Waterfall, senior tranches are repaid first
+            let mut remaining: i128 = amount;
+            for (tranche, tranche_info) in read_tranches(&e).iter().enumerate() {
+                let tranche: u32 = tranche as u32;
+                let due: i128 = tranche_payback(&e, tranche) - read_tranche_repaid(&e, tranche);
+                let allocated: i128 = remaining.min(due);
+                if allocated <= 0 {
+                    continue;
+                }
+                let reward_rate: i128 = tranche_info.reward_rate;
+                let base_return: i128 =
+                    mul_div(&e, allocated, REWARD_DENOM, REWARD_DENOM + reward_rate, Rounding::Down);
+                let tranche_fee: i128 = mul_div(
+                    &e,
+                    base_return,
+                    reward_rate * read_protocol_fee(&e),
+                    REWARD_DENOM * REWARD_DENOM,
+                    Rounding::Down,
+                );
+                repay_tranche(&e, tranche, allocated, base_return, tranche_fee, allocated == due);
+                protocol_fee += tranche_fee;
+                remaining -= allocated;
+            }
+            if remaining > 0 {
+                panic_with_error!(&e, Error::ReturnOverreached);
+            }
+        }
 
         let key_return: DataKey = DataKey::TotalReturn;
         let mut total_return: i128 = e.storage().persistent().get(&key_return).unwrap_or(0);
@@ -490,10 +1357,6 @@ impl EnerDAOToken {
         total_fee += protocol_fee;
         e.storage().persistent().set(&key_fee, &total_fee);
 
-        
-        if total_return + total_fee > project_info.target_amount * (REWARD_DENOM + reward_rate) / REWARD_DENOM {
-            panic_with_error!(&e, Error::ReturnOverreached);
-        }
 
         move_token(
             &e,
@@ -502,8 +1365,14 @@ impl EnerDAOToken {
             protocol_fee,
         );
 
-        let key_claim: DataKey = DataKey::ClaimAvailable;
-        e.storage().persistent().set(&key_claim, &true);
+        if Self::borrower_to_payback(&e) == 0 {
+            transition(&e, ProjectState::Repaid);
+            if read_collateral_outcome(&e).is_none() && has_nft_collateral(&e) {
+                release_collateral_to_depositor(&e);
+            }
+        } else if state == ProjectState::Disbursed {
+            transition(&e, ProjectState::Repaying);
+        }
 
         e.events().publish(
             (Symbol::new(&e, "borrower_return"), borrower.clone()),
@@ -520,65 +1389,809 @@ impl EnerDAOToken {
     }
 
     pub fn mint(e: Env, to: Address, amount: i128) {
-        check_nonnegative_amount(&e, amount);
         let admin = read_administrator(&e);
         admin.require_auth();
+        require_no_timelock(&e);
 
-        _mint(e, to, amount);
+        admin_mint(&e, to, amount);
     }
 
-    pub fn set_admin(e: Env, new_admin: Address) {
+    // First step of an admin change, the new admin takes over with
+    // `accept_admin` once the timelock delay has passed
+    pub fn propose_admin(e: Env, new_admin: Address) {
         let admin = read_administrator(&e);
         admin.require_auth();
 
+        let eta: u64 = timelock_eta(&e);
+        write_pending_admin(
+            &e,
+            &PendingAdmin {
+                admin: new_admin.clone(),
+                eta,
+            },
+        );
+
+        e.events()
+            .publish((Symbol::new(&e, "admin_proposed"), admin), (new_admin, eta));
+    }
+
+    pub fn accept_admin(e: Env) {
+        let pending_admin: PendingAdmin = match read_pending_admin(&e) {
+            Some(pending_admin) => pending_admin,
+            None => panic_with_error!(&e, Error::NoPendingAdmin),
+        };
+        pending_admin.admin.require_auth();
+        if e.ledger().timestamp() < pending_admin.eta {
+            panic_with_error!(&e, Error::TimelockNotExpired)
+        }
+
         e.storage()
             .instance()
             .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 
-        write_administrator(&e, &new_admin);
-        TokenUtils::new(&e).events().set_admin(admin, new_admin);
+        let admin = read_administrator(&e);
+        write_administrator(&e, &pending_admin.admin);
+        remove_pending_admin(&e);
+        TokenUtils::new(&e).events().set_admin(admin, pending_admin.admin);
     }
 
-    pub fn set_project_info(e: Env, new_project_info: ProjectInfo) {
+    pub fn cancel_admin_proposal(e: Env) {
         let admin = read_administrator(&e);
         admin.require_auth();
 
-        let project_key: DataKey = DataKey::ProjectInfo;
-        e.storage()
-            .persistent()
-            .set(&project_key, &new_project_info);
+        let pending_admin: PendingAdmin = match read_pending_admin(&e) {
+            Some(pending_admin) => pending_admin,
+            None => panic_with_error!(&e, Error::NoPendingAdmin),
+        };
+        remove_pending_admin(&e);
+
+        e.events().publish(
+            (Symbol::new(&e, "admin_proposal_cancelled"), admin),
+            pending_admin.admin,
+        );
     }
 
-    pub fn set_lender_claim_available(e: Env, is_available: bool, target_not_reached: bool) {
+    pub fn pending_admin(e: Env) -> Option<PendingAdmin> {
+        read_pending_admin(&e)
+    }
+
+    // Adds `account` to the holders of a role, a role can have any number of holders
+    pub fn grant_role(e: Env, role: Role, account: Address) {
         let admin = read_administrator(&e);
         admin.require_auth();
 
-        let key_claim: DataKey = DataKey::ClaimAvailable;
-        e.storage().persistent().set(&key_claim, &is_available);
-
-        let key_target: DataKey = DataKey::TargetNotReached;
-        e.storage()
-            .persistent()
-            .set(&key_target, &target_not_reached);
+        if write_role_holder(&e, role, &account) {
+            e.events()
+                .publish((Symbol::new(&e, "role_granted"), role), account);
+        }
     }
 
-    pub fn grant_nft(e: Env, to: Address) {
+    // The administrator acts in the role again once its last holder is revoked
+    pub fn revoke_role(e: Env, role: Role, account: Address) {
         let admin = read_administrator(&e);
         admin.require_auth();
 
-        let collateral_nft_address: Address = get_project_info(&e).collateral_nft_address;
-        let collateral_id: u128 = get_project_info(&e).collateral_id;
-        // transfer NFT
-        let nft_client = contract_nft::Client::new(&e, &collateral_nft_address);
-        nft_client.transfer(&e.current_contract_address(), &to, &collateral_id);
+        remove_role_holder(&e, role, &account);
+        e.events()
+            .publish((Symbol::new(&e, "role_revoked"), role), account);
     }
 
-    pub fn rescue_tokens(e: Env, token_address: Address, to: Address, amount: i128) {
+    pub fn has_role(e: Env, role: Role, account: Address) -> bool {
+        has_role(&e, role, &account)
+    }
+
+    pub fn role_holder_count(e: Env, role: Role) -> u32 {
+        read_role_holder_count(&e, role)
+    }
+
+    pub fn pause(e: Env, caller: Address, functions: Vec<PausableFunction>) {
+        require_role(&e, Role::Pauser, &caller);
+
+        for function in functions.iter() {
+            if !is_paused(&e, function) {
+                write_paused_at(&e, function, e.ledger().timestamp());
+                e.events()
+                    .publish((Symbol::new(&e, "paused"), function), ());
+            }
+        }
+    }
+
+    pub fn unpause(e: Env, caller: Address, functions: Vec<PausableFunction>) {
+        require_role(&e, Role::Pauser, &caller);
+
+        for function in functions.iter() {
+            if is_paused(&e, function) {
+                remove_paused_at(&e, function);
+                e.events()
+                    .publish((Symbol::new(&e, "unpaused"), function), ());
+            }
+        }
+    }
+
+    pub fn is_paused(e: Env, function: PausableFunction) -> bool {
+        is_paused(&e, function)
+    }
+
+    pub fn paused_at(e: Env, function: PausableFunction) -> Option<u64> {
+        read_paused_at(&e, function)
+    }
+
+    // How long a pause can last before `emergency_withdraw` opens,
+    // can only be changed before the funding starts
+    pub fn set_max_pause_duration(e: Env, caller: Address, duration: u64) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        if duration == 0 {
+            panic_with_error!(&e, Error::InvalidPauseDuration)
+        }
+
+        write_max_pause_duration(&e, duration);
+        e.events()
+            .publish((Symbol::new(&e, "max_pause_duration"),), duration);
+    }
+
+    pub fn max_pause_duration(e: Env) -> u64 {
+        read_max_pause_duration(&e)
+    }
+
+    // True while lenders can use `emergency_withdraw`, only in the
+    // states lenders have something to claim in
+    pub fn is_emergency(e: Env) -> bool {
+        Self::is_lender_claim_available(&e) && is_emergency(&e)
+    }
+
+    // Without a timelock the delay can be set directly, once set
+    // changing it is a sensitive action itself
+    pub fn set_timelock_delay(e: Env, delay: u64) {
         let admin = read_administrator(&e);
         admin.require_auth();
+        require_no_timelock(&e);
+
+        set_timelock_delay(&e, delay);
+    }
+
+    pub fn timelock_delay(e: Env) -> u64 {
+        read_timelock_delay(&e)
+    }
+
+    // Queues a sensitive action, it can be executed once the timelock delay has passed
+    pub fn queue_action(e: Env, caller: Address, action: AdminAction) -> u32 {
+        require_action_authority(&e, &action, &caller);
+
+        let eta: u64 = timelock_eta(&e);
+        let id: u32 = write_queued_action(
+            &e,
+            &QueuedAction {
+                action: action.clone(),
+                eta,
+            },
+        );
+
+        e.events()
+            .publish((Symbol::new(&e, "action_queued"), id), (action, eta));
+        id
+    }
+
+    pub fn execute_action(e: Env, caller: Address, id: u32) {
+
+        let queued_action: QueuedAction = read_queued_action(&e, id);
+        require_action_authority(&e, &queued_action.action, &caller);
+        if e.ledger().timestamp() < queued_action.eta {
+            panic_with_error!(&e, Error::TimelockNotExpired)
+        }
+        remove_queued_action(&e, id);
+
+        match queued_action.action.clone() {
+            AdminAction::SetProjectField(field) => {
+                require_field_editable(&e, &field);
+                write_project_field(&e, field)
+            }
+            AdminAction::RescueTokens(token_address, to, amount) => {
+                rescue_tokens(&e, token_address, to, amount)
+            }
+            AdminAction::Upgrade(new_wasm_hash) => upgrade(&e, new_wasm_hash),
+            AdminAction::SetTimelockDelay(delay) => set_timelock_delay(&e, delay),
+            AdminAction::Mint(to, amount) => admin_mint(&e, to, amount),
+            AdminAction::Burn(from, amount) => admin_burn(&e, from, amount),
+        }
+
+        e.events()
+            .publish((Symbol::new(&e, "action_executed"), id), queued_action.action);
+    }
+
+    pub fn cancel_action(e: Env, caller: Address, id: u32) {
+
+        let queued_action: QueuedAction = read_queued_action(&e, id);
+        require_action_authority(&e, &queued_action.action, &caller);
+        remove_queued_action(&e, id);
+
+        e.events()
+            .publish((Symbol::new(&e, "action_cancelled"), id), queued_action.action);
+    }
+
+    pub fn queued_action(e: Env, id: u32) -> Option<QueuedAction> {
+        if has_queued_action(&e, id) {
+            Some(read_queued_action(&e, id))
+        } else {
+            None
+        }
+    }
+
+    // Material changes once lenders have committed funds go through
+    // a proposal the LP holders vote on
+    pub fn set_governance_config(e: Env, caller: Address, config: GovernanceConfig) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        if config.quorum < 0
+            || config.quorum > REWARD_DENOM
+            || config.threshold < 0
+            || config.threshold >= REWARD_DENOM
+            || config.voting_period == 0
+        {
+            panic_with_error!(&e, GovernanceError::InvalidGovernanceConfig)
+        }
+
+        write_governance_config(&e, &config);
+        e.events()
+            .publish((Symbol::new(&e, "governance_config"),), config);
+    }
+
+    pub fn governance_config(e: Env) -> GovernanceConfig {
+        read_governance_config(&e)
+    }
+
+    // Opens a vote on the action, LP holders vote with their balance
+    // before the proposal was created
+    pub fn propose(e: Env, caller: Address, action: ProposalAction) -> u32 {
+        require_role(&e, Role::Operator, &caller);
+
+        sync_state(&e);
+        require_valid_proposal_action(&e, &action);
+
+        let now: u64 = e.ledger().timestamp();
+        let supply: i128 = read_supply_before(&e, now);
+        if supply <= 0 {
+            panic_with_error!(&e, GovernanceError::NoVotingPower)
+        }
+        let proposal: Proposal = Proposal {
+            action: action.clone(),
+            proposer: caller.clone(),
+            snapshot: now,
+            end_timestamp: now + read_governance_config(&e).voting_period,
+            supply,
+            votes_for: 0,
+            votes_against: 0,
+            status: ProposalStatus::Active,
+        };
+        let id: u32 = next_proposal_id(&e);
+        write_proposal(&e, id, &proposal);
+
+        e.events().publish(
+            (Symbol::new(&e, "proposal_created"), id),
+            (action, proposal.end_timestamp),
+        );
+        id
+    }
+
+    pub fn vote(e: Env, voter: Address, proposal_id: u32, support: bool) {
+        voter.require_auth();
+
+        let mut proposal: Proposal = read_proposal(&e, proposal_id);
+        if proposal_status(&e, &proposal) != ProposalStatus::Active {
+            panic_with_error!(&e, GovernanceError::VotingClosed)
+        }
+        if has_voted(&e, proposal_id, voter.clone()) {
+            panic_with_error!(&e, GovernanceError::AlreadyVoted)
+        }
+        let weight: i128 = read_balance_before(&e, voter.clone(), proposal.snapshot);
+        if weight <= 0 {
+            panic_with_error!(&e, GovernanceError::NoVotingPower)
+        }
+
+        if support {
+            proposal.votes_for += weight;
+        } else {
+            proposal.votes_against += weight;
+        }
+        write_vote(&e, proposal_id, voter.clone(), weight);
+        write_proposal(&e, proposal_id, &proposal);
+
+        e.events()
+            .publish((Symbol::new(&e, "vote"), proposal_id, voter), (support, weight));
+    }
+
+    // Anyone can execute a proposal once the vote passed
+    pub fn execute_proposal(e: Env, proposal_id: u32) {
+        let mut proposal: Proposal = read_proposal(&e, proposal_id);
+        if proposal_status(&e, &proposal) != ProposalStatus::Passed {
+            panic_with_error!(&e, GovernanceError::ProposalNotPassed)
+        }
+        sync_state(&e);
+        require_valid_proposal_action(&e, &proposal.action);
+
+        apply_proposal_action(&e, proposal.action.clone());
+        proposal.status = ProposalStatus::Executed;
+        write_proposal(&e, proposal_id, &proposal);
+
+        e.events()
+            .publish((Symbol::new(&e, "proposal_executed"), proposal_id), proposal.action);
+    }
+
+    pub fn cancel_proposal(e: Env, caller: Address, proposal_id: u32) {
+        require_role(&e, Role::Operator, &caller);
+
+        let mut proposal: Proposal = read_proposal(&e, proposal_id);
+        match proposal_status(&e, &proposal) {
+            ProposalStatus::Executed | ProposalStatus::Cancelled => {
+                panic_with_error!(&e, GovernanceError::InvalidProposal)
+            }
+            _ => {}
+        }
+        proposal.status = ProposalStatus::Cancelled;
+        write_proposal(&e, proposal_id, &proposal);
+
+        e.events()
+            .publish((Symbol::new(&e, "proposal_cancelled"), proposal_id), ());
+    }
+
+    // The proposal with its current status
+    pub fn get_proposal(e: Env, proposal_id: u32) -> Proposal {
+        let mut proposal: Proposal = read_proposal(&e, proposal_id);
+        proposal.status = proposal_status(&e, &proposal);
+        proposal
+    }
+
+    pub fn voting_power(e: Env, voter: Address, proposal_id: u32) -> i128 {
+        let proposal: Proposal = read_proposal(&e, proposal_id);
+        read_balance_before(&e, voter, proposal.snapshot)
+    }
+
+    // Changes a single field of the project info, see `require_field_editable`
+    // for the fields that can still change
+    pub fn set_project_field(e: Env, caller: Address, field: ProjectField) {
+        require_role(&e, Role::Operator, &caller);
+        require_no_timelock(&e);
+
+        require_field_editable(&e, &field);
+        write_project_field(&e, field);
+    }
+
+    // Stops a project that has not been disbursed yet, lenders get their funds back
+    pub fn cancel_project(e: Env, caller: Address) {
+        require_role(&e, Role::Operator, &caller);
+
+        match sync_state(&e) {
+            ProjectState::Created | ProjectState::Funding | ProjectState::Funded => {
+                transition(&e, ProjectState::Refunding)
+            }
+            _ => panic_with_error!(&e, Error::InvalidProjectState),
+        }
+    }
+
+    // Hands the collateral of a defaulted loan to `to`, a repaid loan
+    // releases it to the depositor instead
+    pub fn grant_nft(e: Env, caller: Address, to: Address) {
+        require_role(&e, Role::CollateralManager, &caller);
+        if sync_state(&e) != ProjectState::Defaulted {
+            panic_with_error!(&e, Error::NotDefaulted)
+        }
+        require_nft_collateral(&e);
+
+        // transfer NFT
+        transfer_collateral(&e, &to);
+        write_collateral_outcome(&e, CollateralStatus::Seized);
+
+        e.events().publish(
+            (Symbol::new(&e, "collateral_granted"), to),
+            get_project_info(&e).collateral_id,
+        );
+    }
+
+    // Pulls the collateral NFT from the borrower into the contract, it is
+    // released back to the borrower once the loan is repaid
+    pub fn deposit_collateral(e: Env, borrower: Address) {
+        borrower.require_auth();
+        let project_info: ProjectInfo = get_project_info(&e);
+        if borrower != project_info.borrower {
+            panic_with_error!(&e, Error::NotAllowed)
+        }
+        match sync_state(&e) {
+            ProjectState::Created | ProjectState::Funding | ProjectState::Funded => {}
+            _ => panic_with_error!(&e, Error::InvalidProjectState),
+        }
+        if read_collateral_outcome(&e).is_some() || has_nft_collateral(&e) {
+            panic_with_error!(&e, Error::CollateralAlreadyDeposited)
+        }
 
-        let token_client: token::TokenClient<'_> = token::Client::new(&e, &token_address);
-        token_client.transfer(&e.current_contract_address(), &to, &amount);
+        let nft_client = contract_nft::Client::new(&e, &project_info.collateral_nft_address);
+        nft_client.transfer(
+            &borrower,
+            &e.current_contract_address(),
+            &project_info.collateral_id,
+        );
+        require_nft_collateral(&e);
+        write_collateral_depositor(&e, &borrower);
+
+        e.events().publish(
+            (Symbol::new(&e, "collateral_deposited"), borrower),
+            project_info.collateral_id,
+        );
+    }
+
+    pub fn collateral_depositor(e: Env) -> Option<Address> {
+        read_collateral_depositor(&e)
+    }
+
+    pub fn set_fee_bounds(e: Env, caller: Address, min_fee: i128, max_fee: i128) {
+        require_role(&e, Role::Treasurer, &caller);
+
+        if min_fee < 0 || min_fee > max_fee || max_fee > REWARD_DENOM {
+            panic_with_error!(&e, Error::FeeOutOfBounds)
+        }
+
+        let mut config: FeeConfig = read_fee_config(&e);
+        config.min_fee = min_fee;
+        config.max_fee = max_fee;
+        write_fee_config(&e, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "fee_bounds"),), (min_fee, max_fee));
+    }
+
+    // The fee can only be changed before the funding starts
+    pub fn set_protocol_fee(e: Env, caller: Address, protocol_fee: i128) {
+        require_role(&e, Role::Treasurer, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FeeFrozen)
+        }
+
+        let mut config: FeeConfig = read_fee_config(&e);
+        if protocol_fee < config.min_fee || protocol_fee > config.max_fee {
+            panic_with_error!(&e, Error::FeeOutOfBounds)
+        }
+        let old_fee: i128 = config.protocol_fee;
+        config.protocol_fee = protocol_fee;
+        write_fee_config(&e, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "fee_changed"),), (old_fee, protocol_fee));
+    }
+
+    pub fn fee_config(e: Env) -> FeeConfig {
+        read_fee_config(&e)
+    }
+
+    // Soft cap, `None` requires the whole target amount.
+    // Can only be changed before the funding starts.
+    pub fn set_min_amount(e: Env, caller: Address, min_amount: Option<i128>) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        if let Some(min_amount) = min_amount {
+            if min_amount <= 0 || min_amount > get_project_info(&e).target_amount {
+                panic_with_error!(&e, Error::InvalidSoftCap)
+            }
+        }
+        write_min_amount(&e, &min_amount);
+
+        e.events()
+            .publish((Symbol::new(&e, "min_amount"),), min_amount);
+    }
+
+    pub fn min_amount(e: Env) -> Option<i128> {
+        read_min_amount(&e)
+    }
+
+    // Can only be changed before the funding starts
+    pub fn set_pledge_lock_period(e: Env, caller: Address, lock_period: u64) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        write_pledge_lock_period(&e, lock_period);
+
+        e.events()
+            .publish((Symbol::new(&e, "pledge_lock_period"),), lock_period);
+    }
+
+    pub fn pledge_lock_period(e: Env) -> u64 {
+        read_pledge_lock_period(&e)
+    }
+
+    // Per-lender limits, `None` disables the cap.
+    // Can only be changed before the funding starts.
+    pub fn set_lend_limits(
+        e: Env,
+        caller: Address,
+        min_lend_amount: i128,
+        max_lend_amount: Option<i128>,
+        max_concentration: Option<i128>,
+    ) {
+        require_role(&e, Role::Operator, &caller);
+
+        let fields: Vec<ProjectField> = vec![
+            &e,
+            ProjectField::MinLendAmount(min_lend_amount),
+            ProjectField::MaxLendAmount(max_lend_amount),
+            ProjectField::MaxConcentration(max_concentration),
+        ];
+        for field in fields.iter() {
+            require_field_editable(&e, &field);
+        }
+        write_project_fields(&e, fields);
+    }
+
+    // Switches the project to accruing interest, can only be changed before the
+    // funding starts. Repayment schedules need the flat reward rate.
+    pub fn set_interest_config(e: Env, caller: Address, config: InterestConfig) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        if config.apr < 0
+            || config.apr > MAX_APR
+            || (config.mode == InterestMode::Compound && config.compounding_period == 0)
+            || (config.mode != InterestMode::Flat && number_of_tranches(&e) > 1)
+            || (config.mode != InterestMode::Flat && !read_schedule(&e).is_empty())
+        {
+            panic_with_error!(&e, Error::InvalidInterestConfig)
+        }
+        write_interest_config(&e, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "interest_config"),), config);
+    }
+
+    pub fn interest_config(e: Env) -> InterestConfig {
+        read_interest_config(&e)
+    }
+
+    // Tranches in order of seniority, their targets have to add up to the
+    // target amount. Can only be changed before the funding starts, projects
+    // with several tranches use the flat reward rates without a schedule.
+    pub fn set_tranches(e: Env, caller: Address, tranches: Vec<Tranche>) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        let mut total_target: i128 = 0;
+        for tranche in tranches.iter() {
+            if tranche.target_amount <= 0 || tranche.reward_rate < 0 {
+                panic_with_error!(&e, Error::InvalidTranches)
+            }
+            total_target += tranche.target_amount;
+        }
+        if tranches.is_empty()
+            || total_target != get_project_info(&e).target_amount
+            || (tranches.len() > 1 && (is_accruing(&e) || !read_schedule(&e).is_empty()))
+        {
+            panic_with_error!(&e, Error::InvalidTranches)
+        }
+        write_tranches(&e, &tranches);
+
+        e.events()
+            .publish((Symbol::new(&e, "tranches"),), tranches);
+    }
+
+    pub fn tranches(e: Env) -> Vec<Tranche> {
+        read_tranches(&e)
+    }
+
+    pub fn tranche_supply(e: Env, tranche: u32) -> i128 {
+        read_tranche_supply(&e, tranche)
+    }
+
+    pub fn tranche_balance(e: Env, tranche: u32, id: Address) -> i128 {
+        read_tranche_balance(&e, tranche, id)
+    }
+
+    // Part of the payback still owed to the tranche
+    pub fn tranche_to_payback(e: Env, tranche: u32) -> i128 {
+        tranche_payback(&e, tranche) - read_tranche_repaid(&e, tranche)
+    }
+
+    // Rounding remainders of the repaid tranches, they stay in the contract
+    pub fn dust(e: Env) -> i128 {
+        read_dust(&e)
+    }
+
+    // LP transfer within a tranche, needed once a project has several tranches
+    pub fn transfer_tranche(e: Env, from: Address, to: Address, tranche: u32, amount: i128) {
+        from.require_auth();
+
+        check_nonnegative_amount(&e, amount);
+        read_tranche(&e, tranche);
+
+        e.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        _transfer(&e, tranche, from, to, amount);
+    }
+
+    pub fn set_kyc_provider(e: Env, caller: Address, provider: Address) {
+        require_role(&e, Role::Compliance, &caller);
+
+        write_kyc_provider(&e, &provider);
+        e.events()
+            .publish((Symbol::new(&e, "kyc_provider"),), provider);
+    }
+
+    // In allowlist mode only approved addresses can lend or receive LP tokens
+    pub fn set_kyc_required(e: Env, caller: Address, required: bool) {
+        require_role(&e, Role::Compliance, &caller);
+
+        write_kyc_required(&e, required);
+        e.events()
+            .publish((Symbol::new(&e, "kyc_required"),), required);
+    }
+
+    // Adds an address to the allowlist without expiry
+    pub fn kyc_approve(e: Env, addr: Address) {
+        Self::kyc_attest(e, addr, u64::MAX);
+    }
+
+    // Approves an address until `expires_at`
+    pub fn kyc_attest(e: Env, addr: Address, expires_at: u64) {
+        let provider: Address = read_kyc_provider_or_panic(&e);
+        provider.require_auth();
+
+        write_kyc_approval(&e, addr.clone(), expires_at);
+        e.events()
+            .publish((Symbol::new(&e, "kyc_approved"), addr), expires_at);
+    }
+
+    pub fn kyc_revoke(e: Env, addr: Address) {
+        let provider: Address = read_kyc_provider_or_panic(&e);
+        provider.require_auth();
+
+        remove_kyc_approval(&e, addr.clone());
+        e.events()
+            .publish((Symbol::new(&e, "kyc_revoked"), addr), ());
+    }
+
+    pub fn kyc_provider(e: Env) -> Option<Address> {
+        read_kyc_provider(&e)
+    }
+
+    pub fn is_kyc_required(e: Env) -> bool {
+        read_kyc_required(&e)
+    }
+
+    pub fn kyc_status(e: Env, addr: Address) -> KycStatus {
+        read_kyc_status(&e, addr)
+    }
+
+    // True if the address can lend or receive LP tokens
+    pub fn is_kyc_approved(e: Env, addr: Address) -> bool {
+        is_kyc_approved(&e, &addr)
+    }
+
+    pub fn set_default_config(e: Env, caller: Address, config: DefaultConfig) {
+        require_role(&e, Role::Operator, &caller);
+
+        if sync_state(&e) != ProjectState::Created || read_total_supply(&e) > 0 {
+            panic_with_error!(&e, Error::FundingStarted)
+        }
+        if config.installment_grace_period > MAX_GRACE_PERIOD
+            || config.maturity_grace_period > MAX_GRACE_PERIOD
+        {
+            panic_with_error!(&e, DefaultError::InvalidGracePeriod)
+        }
+
+        write_default_config(&e, &config);
+        e.events()
+            .publish((Symbol::new(&e, "default_config"),), config);
+    }
+
+    pub fn default_config(e: Env) -> Option<DefaultConfig> {
+        read_default_config(&e)
+    }
+
+    // True if check_default would move the project to Defaulted
+    pub fn is_default_due(e: Env) -> bool {
+        matches!(
+            current_state(&e),
+            ProjectState::Disbursed | ProjectState::Repaying
+        ) && is_payment_late(&e)
+    }
+
+    // Can be called by anyone once a payment is late beyond its grace period
+    pub fn check_default(e: Env) {
+        match sync_state(&e) {
+            ProjectState::Disbursed | ProjectState::Repaying => {}
+            _ => panic_with_error!(&e, Error::InvalidProjectState),
+        }
+        if !is_payment_late(&e) {
+            panic_with_error!(&e, Error::NotDefaulted)
+        }
+
+        transition(&e, ProjectState::Defaulted);
+        e.storage()
+            .persistent()
+            .set(&DataKey::DefaultedAt, &e.ledger().timestamp());
+
+        e.events().publish(
+            (Symbol::new(&e, "default"), get_project_info(&e).borrower),
+            Self::borrower_to_payback(&e),
+        );
+    }
+
+    // Lender-controlled address (a vault or an auction contract)
+    // that receives the collateral of a defaulted loan
+    pub fn set_collateral_vault(e: Env, caller: Address, vault: Address) {
+        require_role(&e, Role::CollateralManager, &caller);
+
+        write_collateral_vault(&e, &vault);
+        e.events()
+            .publish((Symbol::new(&e, "collateral_vault"),), vault);
+    }
+
+    pub fn seize_collateral(e: Env) {
+        if sync_state(&e) != ProjectState::Defaulted {
+            panic_with_error!(&e, Error::InvalidProjectState)
+        }
+        let vault: Address = match read_collateral_vault(&e) {
+            Some(vault) => vault,
+            None => panic_with_error!(&e, Error::NoCollateralVault),
+        };
+        require_nft_collateral(&e);
+
+        transfer_collateral(&e, &vault);
+        write_collateral_outcome(&e, CollateralStatus::Seized);
+
+        e.events().publish(
+            (Symbol::new(&e, "collateral_seized"), vault),
+            get_project_info(&e).collateral_id,
+        );
+    }
+
+    // Returns the collateral to the depositor once the loan is repaid
+    // or the project was not funded. The final borrower_return releases
+    // it on its own, this covers refunds and NFTs that arrived later.
+    pub fn release_collateral(e: Env) {
+        match sync_state(&e) {
+            ProjectState::Repaid | ProjectState::Refunding | ProjectState::Closed => {}
+            _ => panic_with_error!(&e, Error::InvalidProjectState),
+        }
+        require_nft_collateral(&e);
+
+        release_collateral_to_depositor(&e);
+    }
+
+    pub fn collateral_status(e: Env) -> CollateralStatus {
+        if let Some(status) = read_collateral_outcome(&e) {
+            return status;
+        }
+        if has_nft_collateral(&e) {
+            CollateralStatus::Held
+        } else {
+            CollateralStatus::Missing
+        }
+    }
+
+    pub fn rescue_tokens(e: Env, caller: Address, token_address: Address, to: Address, amount: i128) {
+        require_role(&e, Role::Treasurer, &caller);
+        require_no_timelock(&e);
+
+        rescue_tokens(&e, token_address, to, amount);
+    }
+
+    // Lend tokens `rescue_tokens` can transfer without touching lender funds
+    pub fn surplus(e: Env) -> i128 {
+        lend_token_surplus(&e)
     }
 
     pub fn get_project_info(e: Env) -> ProjectInfo {
@@ -599,13 +2212,49 @@ impl EnerDAOToken {
         read_total_supply(&e)
     }
 
+    // Project card data in one call, the payback is only outstanding once disbursed
+    pub fn project_summary(e: Env) -> ProjectSummary {
+        let project_info: ProjectInfo = get_project_info(&e);
+        let now: u64 = e.ledger().timestamp();
+        let raised_amount: i128 = if is_disbursed(&e) {
+            principal(&e)
+        } else {
+            read_total_supply(&e)
+        };
+        let outstanding_payback: i128 = if is_disbursed(&e) {
+            Self::borrower_to_payback(&e)
+        } else {
+            0
+        };
+        ProjectSummary {
+            state: current_state(&e),
+            target_amount: project_info.target_amount,
+            raised_amount,
+            remaining_to_target: (project_info.target_amount - raised_amount).max(0),
+            total_return: Self::total_return(e.clone()),
+            fee_accumulated: Self::fee_accumulated(e.clone()),
+            outstanding_payback,
+            number_of_lenders: read_number_of_lenders(&e),
+            seconds_to_start: project_info.start_timestamp.saturating_sub(now),
+            seconds_to_end: project_info.final_timestamp.saturating_sub(now),
+            collateral_status: Self::collateral_status(e.clone()),
+        }
+    }
+
     pub fn number_of_lenders(e: Env) -> u128 {
         read_number_of_lenders(&e)
     }
 
-    pub fn get_lenders(e: Env) -> Vec<Address> {
+    // Lenders with an LP balance, at most `limit` of them (capped at
+    // MAX_PAGE_SIZE) skipping the first `offset` ones. Lenders are removed
+    // once their balance is zero, so the order changes over time.
+    pub fn get_lenders(e: Env, offset: u32, limit: u32) -> Vec<Address> {
         let mut lenders: Vec<Address> = Vec::<Address>::new(&e);
-        for i in 1..=read_number_of_lenders(&e) {
+        let number_of_lenders: u128 = read_number_of_lenders(&e);
+        let limit: u32 = limit.min(MAX_PAGE_SIZE);
+        let first: u128 = offset as u128 + 1;
+        let last: u128 = (offset as u128 + limit as u128).min(number_of_lenders);
+        for i in first..=last {
             let user_address: Address = e
                 .storage()
                 .persistent()
@@ -616,6 +2265,26 @@ impl EnerDAOToken {
         lenders
     }
 
+    pub fn lender_position(e: Env, lender: Address) -> LenderPosition {
+        let balance: i128 = read_balance(&e, lender.clone());
+        let total_supply: i128 = read_total_supply(&e);
+        let available: i128 = (0..number_of_tranches(&e))
+            .map(|tranche| Self::lender_available_to_claim(e.clone(), lender.clone(), tranche))
+            .sum();
+        let share: i128 = if total_supply > 0 {
+            mul_div(&e, balance, REWARD_DENOM, total_supply, Rounding::Down)
+        } else {
+            0
+        };
+        LenderPosition {
+            balance,
+            claimed: read_claimed(&e, lender.clone()),
+            available,
+            share,
+            first_lend_at: read_first_lend_at(&e, lender),
+        }
+    }
+
     #[cfg(test)]
     pub fn get_allowance(e: Env, from: Address, spender: Address) -> Option<AllowanceValue> {
         let key = DataKey::Allowance(AllowanceDataKey { from, spender });
@@ -665,11 +2334,8 @@ impl token::Interface for EnerDAOToken {
             .instance()
             .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 
-        transfer_claimed(&e, &from, &to, amount);
-        spend_balance(&e, from.clone(), amount);
-        receive_balance(&e, to.clone(), amount);
-        _add_lender(e.clone(), to.clone());
-        TokenUtils::new(&e).events().transfer(from, to, amount);
+        let tranche: u32 = single_tranche(&e);
+        _transfer(&e, tranche, from, to, amount);
     }
 
     fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
@@ -682,12 +2348,9 @@ impl token::Interface for EnerDAOToken {
             .instance()
             .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 
-        transfer_claimed(&e, &from, &to, amount);
+        let tranche: u32 = single_tranche(&e);
         spend_allowance(&e, from.clone(), spender, amount);
-        spend_balance(&e, from.clone(), amount);
-        receive_balance(&e, to.clone(), amount);
-        _add_lender(e.clone(), to.clone());
-        TokenUtils::new(&e).events().transfer(from, to, amount)
+        _transfer(&e, tranche, from, to, amount);
     }
 
     fn burn(e: Env, from: Address, amount: i128) {
@@ -695,10 +2358,9 @@ impl token::Interface for EnerDAOToken {
         // Admin only
         let admin = read_administrator(&e);
         admin.require_auth();
+        require_no_timelock(&e);
 
-        check_nonnegative_amount(&e, amount);
-
-        _burn(e, from, amount);
+        admin_burn(&e, from, amount);
     }
 
     fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
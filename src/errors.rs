@@ -1,5 +1,11 @@
 use soroban_sdk::contracterror;
 
+// Every error enum has its own range of codes, so a code always maps to a
+// single error: Error 100-149, GovernanceError 200-249, ProjectInfoError
+// 300-349 and DefaultError 400-449. A contract error enum takes at most 50
+// variants, Error is full.
+
+// General errors, codes 100-149
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -15,5 +21,77 @@ pub enum Error {
     NothingToClaim = 108,
     AlreadyClaimed = 109,
     ReturnOverreached = 110,
+    InvalidProjectState = 111,
+    InvalidStateTransition = 112,
+    InvalidSchedule = 113,
+    NotDefaulted = 114,
+    NoCollateralVault = 115,
+    FeeFrozen = 116,
+    FeeOutOfBounds = 117,
+    FundingStarted = 118,
+    InvalidSoftCap = 119,
     NotAllowed = 120,
+    PledgeLocked = 121,
+    NotKycApproved = 122,
+    NoKycProvider = 123,
+    LendBelowMinimum = 124,
+    LendAboveMaximum = 125,
+    ConcentrationExceeded = 126,
+    InvalidLendLimits = 127,
+    InvalidInterestConfig = 128,
+    InvalidTranche = 129,
+    InvalidTranches = 130,
+    TrancheRequired = 131,
+    MathOverflow = 132,
+    InsufficientBalance = 133,
+    InsufficientAllowance = 134,
+    InvalidExpirationLedger = 135,
+    NotInitialized = 136,
+    AlreadyMigrated = 137,
+    UnsupportedMigration = 138,
+    NoPendingAdmin = 139,
+    TimelockNotExpired = 140,
+    TimelockRequired = 141,
+    ActionNotQueued = 142,
+    RoleNotGranted = 143,
+    FunctionPaused = 144,
+    NoEmergency = 145,
+    InvalidPauseDuration = 146,
+    InsufficientSurplus = 147,
+    CollateralAlreadyDeposited = 148,
+    InvalidTimelockDelay = 149,
+}
+
+// Errors of the proposals and votes, codes 200-249
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GovernanceError {
+    InvalidGovernanceConfig = 200,
+    ProposalNotFound = 201,
+    VotingClosed = 202,
+    AlreadyVoted = 203,
+    NoVotingPower = 204,
+    ProposalNotPassed = 205,
+    InvalidProposal = 206,
+}
+
+// Errors of the project setup and updates, codes 300-349
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ProjectInfoError {
+    ProjectAlreadyInitialized = 300,
+    InvalidTargetAmount = 301,
+    InvalidTimestamps = 302,
+    InvalidRewardRate = 303,
+    FieldFrozen = 304,
+}
+
+// Errors of the default settings, codes 400-449
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DefaultError {
+    InvalidGracePeriod = 400,
 }
@@ -0,0 +1,26 @@
+use crate::storage_types::{
+    DataKey, FeeConfig, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, DEFAULT_PROTOCOL_FEE,
+    REWARD_DENOM,
+};
+use soroban_sdk::Env;
+
+pub fn read_fee_config(e: &Env) -> FeeConfig {
+    let key = DataKey::FeeConfig;
+    e.storage().persistent().get(&key).unwrap_or(FeeConfig {
+        protocol_fee: DEFAULT_PROTOCOL_FEE,
+        min_fee: 0,
+        max_fee: REWARD_DENOM,
+    })
+}
+
+pub fn write_fee_config(e: &Env, config: &FeeConfig) {
+    let key = DataKey::FeeConfig;
+    e.storage().persistent().set(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn read_protocol_fee(e: &Env) -> i128 {
+    read_fee_config(e).protocol_fee
+}
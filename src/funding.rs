@@ -0,0 +1,37 @@
+use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::Env;
+
+// Soft cap, a project raising at least this amount by
+// final_timestamp is funded even if the target is not reached
+pub fn read_min_amount(e: &Env) -> Option<i128> {
+    let key = DataKey::MinAmount;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_min_amount(e: &Env, min_amount: &Option<i128>) {
+    let key = DataKey::MinAmount;
+    match min_amount {
+        Some(min_amount) => {
+            e.storage().persistent().set(&key, min_amount);
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        }
+        None => e.storage().persistent().remove(&key),
+    }
+}
+
+// Pledges can not be withdrawn during the last
+// `lock_period` seconds before final_timestamp
+pub fn read_pledge_lock_period(e: &Env) -> u64 {
+    let key = DataKey::PledgeLockPeriod;
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn write_pledge_lock_period(e: &Env, lock_period: u64) {
+    let key = DataKey::PledgeLockPeriod;
+    e.storage().persistent().set(&key, &lock_period);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
@@ -0,0 +1,195 @@
+use crate::errors::GovernanceError;
+use crate::math::{mul_div, Rounding};
+use crate::storage_types::{
+    Checkpoint, CheckpointDataKey, GovernanceConfig, GovernanceDataKey, Proposal, ProposalStatus, VoteDataKey,
+    BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, REWARD_DENOM,
+};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+pub(crate) const DEFAULT_QUORUM: i128 = 2000;
+pub(crate) const DEFAULT_THRESHOLD: i128 = 5000;
+pub(crate) const DEFAULT_VOTING_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+pub fn read_governance_config(e: &Env) -> GovernanceConfig {
+    let key = GovernanceDataKey::Config;
+    e.storage().persistent().get(&key).unwrap_or(GovernanceConfig {
+        quorum: DEFAULT_QUORUM,
+        threshold: DEFAULT_THRESHOLD,
+        voting_period: DEFAULT_VOTING_PERIOD,
+    })
+}
+
+pub fn write_governance_config(e: &Env, config: &GovernanceConfig) {
+    let key = GovernanceDataKey::Config;
+    e.storage().persistent().set(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+fn read_checkpoint(e: &Env, key: &GovernanceDataKey) -> Checkpoint {
+    e.storage().persistent().get(key).unwrap()
+}
+
+fn write_checkpoint_entry(e: &Env, key: &GovernanceDataKey, checkpoint: &Checkpoint) {
+    e.storage().persistent().set(key, checkpoint);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Appends the amount to the checkpoints, several changes within one
+// ledger timestamp share the last checkpoint and unchanged amounts
+// are not written again
+fn write_checkpoint(
+    e: &Env,
+    count_key: &GovernanceDataKey,
+    checkpoint_key: impl Fn(u32) -> GovernanceDataKey,
+    amount: i128,
+) {
+    let timestamp: u64 = e.ledger().timestamp();
+    let count: u32 = e.storage().persistent().get(count_key).unwrap_or(0);
+    let checkpoint = Checkpoint { timestamp, amount };
+    if count == 0 {
+        if amount == 0 {
+            return;
+        }
+    } else {
+        let last = read_checkpoint(e, &checkpoint_key(count - 1));
+        if last.amount == amount {
+            return;
+        }
+        if last.timestamp == timestamp {
+            write_checkpoint_entry(e, &checkpoint_key(count - 1), &checkpoint);
+            return;
+        }
+    }
+    write_checkpoint_entry(e, &checkpoint_key(count), &checkpoint);
+    e.storage().persistent().set(count_key, &(count + 1));
+    e.storage()
+        .persistent()
+        .extend_ttl(count_key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Amount of the last checkpoint written before `timestamp`,
+// checkpoints are ordered by timestamp so it is a binary search
+fn read_checkpoint_before(
+    e: &Env,
+    count_key: &GovernanceDataKey,
+    checkpoint_key: impl Fn(u32) -> GovernanceDataKey,
+    timestamp: u64,
+) -> i128 {
+    let mut low: u32 = 0;
+    let mut high: u32 = e.storage().persistent().get(count_key).unwrap_or(0);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_checkpoint(e, &checkpoint_key(mid)).timestamp < timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        return 0;
+    }
+    read_checkpoint(e, &checkpoint_key(low - 1)).amount
+}
+
+fn balance_checkpoint_key(addr: &Address) -> impl Fn(u32) -> GovernanceDataKey + '_ {
+    move |index| {
+        GovernanceDataKey::BalanceCheckpoint(CheckpointDataKey {
+            address: addr.clone(),
+            index,
+        })
+    }
+}
+
+pub fn write_balance_checkpoint(e: &Env, addr: Address, balance: i128) {
+    let count_key = GovernanceDataKey::BalanceCheckpointCount(addr.clone());
+    write_checkpoint(e, &count_key, balance_checkpoint_key(&addr), balance);
+}
+
+pub fn read_balance_before(e: &Env, addr: Address, timestamp: u64) -> i128 {
+    let count_key = GovernanceDataKey::BalanceCheckpointCount(addr.clone());
+    read_checkpoint_before(e, &count_key, balance_checkpoint_key(&addr), timestamp)
+}
+
+pub fn write_supply_checkpoint(e: &Env, supply: i128) {
+    write_checkpoint(
+        e,
+        &GovernanceDataKey::SupplyCheckpointCount,
+        GovernanceDataKey::SupplyCheckpoint,
+        supply,
+    );
+}
+
+pub fn read_supply_before(e: &Env, timestamp: u64) -> i128 {
+    read_checkpoint_before(
+        e,
+        &GovernanceDataKey::SupplyCheckpointCount,
+        GovernanceDataKey::SupplyCheckpoint,
+        timestamp,
+    )
+}
+
+pub fn read_proposal(e: &Env, id: u32) -> Proposal {
+    let key = GovernanceDataKey::Proposal(id);
+    match e.storage().persistent().get(&key) {
+        Some(proposal) => proposal,
+        None => panic_with_error!(e, GovernanceError::ProposalNotFound),
+    }
+}
+
+pub fn write_proposal(e: &Env, id: u32, proposal: &Proposal) {
+    let key = GovernanceDataKey::Proposal(id);
+    e.storage().persistent().set(&key, proposal);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Reserves the next proposal id
+pub fn next_proposal_id(e: &Env) -> u32 {
+    let key = GovernanceDataKey::NextProposalId;
+    let id: u32 = e.storage().instance().get(&key).unwrap_or(0);
+    e.storage().instance().set(&key, &(id + 1));
+    id
+}
+
+fn vote_key(proposal_id: u32, voter: Address) -> GovernanceDataKey {
+    GovernanceDataKey::Vote(VoteDataKey { proposal_id, voter })
+}
+
+pub fn has_voted(e: &Env, proposal_id: u32, voter: Address) -> bool {
+    e.storage().persistent().has(&vote_key(proposal_id, voter))
+}
+
+pub fn write_vote(e: &Env, proposal_id: u32, voter: Address, weight: i128) {
+    let key = vote_key(proposal_id, voter);
+    e.storage().persistent().set(&key, &weight);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Executed and cancelled proposals keep their status, the others are
+// active until the end of the voting period and then passed or rejected
+pub fn proposal_status(e: &Env, proposal: &Proposal) -> ProposalStatus {
+    match proposal.status {
+        ProposalStatus::Executed | ProposalStatus::Cancelled => return proposal.status,
+        _ => {}
+    }
+    if e.ledger().timestamp() < proposal.end_timestamp {
+        return ProposalStatus::Active;
+    }
+
+    let config: GovernanceConfig = read_governance_config(e);
+    let votes_cast: i128 = proposal.votes_for + proposal.votes_against;
+    let quorum: i128 = mul_div(e, proposal.supply, config.quorum, REWARD_DENOM, Rounding::Up);
+    let threshold: i128 = mul_div(e, votes_cast, config.threshold, REWARD_DENOM, Rounding::Down);
+    if votes_cast >= quorum && proposal.votes_for > threshold {
+        ProposalStatus::Passed
+    } else {
+        ProposalStatus::Rejected
+    }
+}
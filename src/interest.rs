@@ -0,0 +1,130 @@
+use crate::errors::Error;
+use crate::storage_types::{
+    DataKey, InterestConfig, InterestMode, Loan, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+    REWARD_DENOM,
+};
+use crate::math::{mul_div, Rounding};
+use soroban_sdk::{panic_with_error, Env};
+
+pub(crate) const SECONDS_PER_YEAR: i128 = 365 * 24 * 3600;
+// Highest annual rate of an accruing loan, in bps
+pub(crate) const MAX_APR: i128 = 10000;
+// Precision of the compounding factor
+const FACTOR_SCALE: i128 = 1_000_000_000_000;
+
+pub fn read_interest_config(e: &Env) -> InterestConfig {
+    let key = DataKey::InterestConfig;
+    e.storage().persistent().get(&key).unwrap_or(InterestConfig {
+        mode: InterestMode::Flat,
+        apr: 0,
+        compounding_period: 0,
+    })
+}
+
+pub fn write_interest_config(e: &Env, config: &InterestConfig) {
+    let key = DataKey::InterestConfig;
+    e.storage().persistent().set(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn is_accruing(e: &Env) -> bool {
+    read_interest_config(e).mode != InterestMode::Flat
+}
+
+// Only set for accruing projects, from the disbursement on
+pub fn read_loan(e: &Env) -> Option<Loan> {
+    let key = DataKey::Loan;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_loan(e: &Env, loan: &Loan) {
+    let key = DataKey::Loan;
+    e.storage().persistent().set(&key, loan);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// base^exp in FACTOR_SCALE precision
+fn pow_factor(e: &Env, base: i128, exp: u64) -> i128 {
+    let mut result: i128 = FACTOR_SCALE;
+    let mut base: i128 = base;
+    let mut exp: u64 = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div(e, result, base, FACTOR_SCALE, Rounding::Down);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = mul_div(e, base, base, FACTOR_SCALE, Rounding::Down);
+        }
+    }
+    result
+}
+
+// apr * duration, the rate numerator of `duration` seconds
+fn rate_for(e: &Env, apr: i128, duration: i128) -> i128 {
+    match apr.checked_mul(duration) {
+        Some(rate) => rate,
+        None => panic_with_error!(e, Error::MathOverflow),
+    }
+}
+
+// Loan with the interest accrued until the current ledger timestamp.
+// Compounding only counts whole periods, the rest of the time
+// is carried over to the next accrual.
+pub fn accrue(e: &Env, loan: &Loan) -> Loan {
+    let config: InterestConfig = read_interest_config(e);
+    let now: u64 = e.ledger().timestamp();
+    let mut loan: Loan = loan.clone();
+    if now <= loan.accrued_at {
+        return loan;
+    }
+
+    match config.mode {
+        InterestMode::Flat => {}
+        InterestMode::Simple => {
+            let elapsed: i128 = (now - loan.accrued_at) as i128;
+            loan.interest += mul_div(
+                e,
+                loan.principal,
+                rate_for(e, config.apr, elapsed),
+                REWARD_DENOM * SECONDS_PER_YEAR,
+                Rounding::Down,
+            );
+            loan.accrued_at = now;
+        }
+        InterestMode::Compound => {
+            let periods: u64 = (now - loan.accrued_at) / config.compounding_period;
+            let period_rate: i128 = mul_div(
+                e,
+                FACTOR_SCALE,
+                rate_for(e, config.apr, config.compounding_period as i128),
+                REWARD_DENOM * SECONDS_PER_YEAR,
+                Rounding::Down,
+            );
+            let owed: i128 = loan.principal + loan.interest;
+            let factor: i128 = pow_factor(e, FACTOR_SCALE + period_rate, periods);
+            loan.interest = mul_div(e, owed, factor, FACTOR_SCALE, Rounding::Down) - loan.principal;
+            loan.accrued_at += periods * config.compounding_period;
+        }
+    }
+    loan
+}
+
+// Applies a repayment to the accrued interest first and then to
+// the principal, returns the paid principal and interest
+pub fn repay_loan(e: &Env, amount: i128) -> (i128, i128) {
+    let mut loan: Loan = accrue(e, &read_loan(e).unwrap());
+    let interest_paid: i128 = amount.min(loan.interest);
+    let principal_paid: i128 = amount - interest_paid;
+    if principal_paid > loan.principal {
+        panic_with_error!(e, Error::ReturnOverreached)
+    }
+    loan.interest -= interest_paid;
+    loan.principal -= principal_paid;
+    write_loan(e, &loan);
+    (principal_paid, interest_paid)
+}
@@ -0,0 +1,72 @@
+use crate::errors::Error;
+use crate::storage_types::{
+    DataKey, KycStatus, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+pub fn read_kyc_provider(e: &Env) -> Option<Address> {
+    let key = DataKey::KycProvider;
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_kyc_provider(e: &Env, provider: &Address) {
+    let key = DataKey::KycProvider;
+    e.storage().persistent().set(&key, provider);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn read_kyc_required(e: &Env) -> bool {
+    let key = DataKey::KycRequired;
+    e.storage().persistent().get(&key).unwrap_or(false)
+}
+
+pub fn write_kyc_required(e: &Env, required: bool) {
+    let key = DataKey::KycRequired;
+    e.storage().persistent().set(&key, &required);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Allowlisted addresses are stored with an expiry of u64::MAX
+pub fn write_kyc_approval(e: &Env, addr: Address, expires_at: u64) {
+    let key = DataKey::KycApproval(addr);
+    e.storage().persistent().set(&key, &expires_at);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_kyc_approval(e: &Env, addr: Address) {
+    let key = DataKey::KycApproval(addr);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_kyc_status(e: &Env, addr: Address) -> KycStatus {
+    let key = DataKey::KycApproval(addr);
+    match e.storage().persistent().get::<DataKey, u64>(&key) {
+        None => KycStatus::None,
+        Some(expires_at) if e.ledger().timestamp() > expires_at => KycStatus::Expired,
+        Some(_) => KycStatus::Approved,
+    }
+}
+
+// Holders of LP tokens have to be approved while the allowlist mode is on
+pub fn is_kyc_approved(e: &Env, addr: &Address) -> bool {
+    !read_kyc_required(e) || read_kyc_status(e, addr.clone()) == KycStatus::Approved
+}
+
+pub fn require_kyc(e: &Env, addr: &Address) {
+    if !is_kyc_approved(e, addr) {
+        panic_with_error!(e, Error::NotKycApproved)
+    }
+}
+
+pub fn read_kyc_provider_or_panic(e: &Env) -> Address {
+    match read_kyc_provider(e) {
+        Some(provider) => provider,
+        None => panic_with_error!(e, Error::NoKycProvider),
+    }
+}
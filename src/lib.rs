@@ -3,11 +3,24 @@
 mod admin;
 mod allowance;
 mod balance;
+mod collateral;
 mod contract;
+mod errors;
+mod fee;
+mod funding;
+mod governance;
+mod interest;
+mod kyc;
+mod math;
 mod metadata;
+mod pause;
+mod schedule;
+mod state;
 mod storage_types;
 mod test;
 mod test_token;
-mod errors;
+mod timelock;
+mod tranche;
+mod upgrade;
 
 pub use crate::contract::EnerDAOTokenClient;
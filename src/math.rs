@@ -0,0 +1,40 @@
+use crate::errors::Error;
+use soroban_sdk::{panic_with_error, Env};
+
+// Rounding of a division. Payouts to lenders and protocol fees round down,
+// so the contract never pays out more than it received, amounts owed
+// to the contract round up.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Rounding {
+    Down,
+    Up,
+    // To the nearest value, ties to the even one
+    HalfEven,
+}
+
+// a * b / denominator with checked overflow, for non-negative
+// values and a positive denominator
+pub fn mul_div(e: &Env, a: i128, b: i128, denominator: i128, rounding: Rounding) -> i128 {
+    if a < 0 || b < 0 || denominator <= 0 {
+        panic_with_error!(e, Error::MathOverflow)
+    }
+    let product: i128 = match a.checked_mul(b) {
+        Some(product) => product,
+        None => panic_with_error!(e, Error::MathOverflow),
+    };
+    let quotient: i128 = product / denominator;
+    let remainder: i128 = product % denominator;
+    let round_up: bool = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder > 0,
+        Rounding::HalfEven => {
+            let rest: i128 = denominator - remainder;
+            remainder > rest || (remainder == rest && quotient % 2 == 1)
+        }
+    };
+    if round_up {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
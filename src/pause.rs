@@ -0,0 +1,67 @@
+use crate::errors::Error;
+use crate::storage_types::{
+    DataKey, PausableFunction, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Env};
+
+pub(crate) const DEFAULT_MAX_PAUSE_DURATION: u64 = 30 * 24 * 60 * 60;
+
+// Timestamp the function was paused at, `None` while it is not paused
+pub fn read_paused_at(e: &Env, function: PausableFunction) -> Option<u64> {
+    let key = DataKey::Paused(function);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_paused_at(e: &Env, function: PausableFunction, paused_at: u64) {
+    let key = DataKey::Paused(function);
+    e.storage().persistent().set(&key, &paused_at);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_paused_at(e: &Env, function: PausableFunction) {
+    let key = DataKey::Paused(function);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn is_paused(e: &Env, function: PausableFunction) -> bool {
+    read_paused_at(e, function).is_some()
+}
+
+pub fn require_not_paused(e: &Env, function: PausableFunction) {
+    if is_paused(e, function) {
+        panic_with_error!(e, Error::FunctionPaused)
+    }
+}
+
+// Seconds a pause can last before lenders can withdraw their share
+pub fn read_max_pause_duration(e: &Env) -> u64 {
+    let key = DataKey::MaxPauseDuration;
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(DEFAULT_MAX_PAUSE_DURATION)
+}
+
+pub fn write_max_pause_duration(e: &Env, duration: u64) {
+    let key = DataKey::MaxPauseDuration;
+    e.storage().persistent().set(&key, &duration);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// True once the lender claims or the borrower returns have been paused for
+// longer than the maximum, pausing the other functions does not keep
+// lenders from their funds
+pub fn is_emergency(e: &Env) -> bool {
+    let now: u64 = e.ledger().timestamp();
+    let max_duration: u64 = read_max_pause_duration(e);
+    [PausableFunction::LenderClaim, PausableFunction::BorrowerReturn]
+        .into_iter()
+        .any(|function| match read_paused_at(e, function) {
+            Some(paused_at) => now > paused_at.saturating_add(max_duration),
+            None => false,
+        })
+}
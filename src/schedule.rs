@@ -0,0 +1,150 @@
+use crate::errors::Error;
+use crate::math::{mul_div, Rounding};
+use crate::storage_types::{
+    DataKey, Installment, InstallmentInfo, InstallmentStatus, RepaymentSchedule,
+    BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Env, Vec};
+
+pub fn read_schedule(e: &Env) -> Vec<Installment> {
+    let key = DataKey::Schedule;
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::<Installment>::new(e))
+}
+
+fn write_schedule(e: &Env, installments: &Vec<Installment>) {
+    let key = DataKey::Schedule;
+    e.storage().persistent().set(&key, installments);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+fn build_installments(e: &Env, schedule: RepaymentSchedule, total_payback: i128) -> Vec<Installment> {
+    match schedule {
+        RepaymentSchedule::Installments(installments) => installments,
+        RepaymentSchedule::Amortized(params) => {
+            if params.number_of_installments == 0 || params.period == 0 {
+                panic_with_error!(e, Error::InvalidSchedule)
+            }
+            let number_of_installments = params.number_of_installments as i128;
+            let amount: i128 = total_payback / number_of_installments;
+            let mut installments: Vec<Installment> = Vec::<Installment>::new(e);
+            for i in 0..params.number_of_installments {
+                let due_timestamp: u64 = match params
+                    .period
+                    .checked_mul(i as u64)
+                    .and_then(|offset| params.first_due_timestamp.checked_add(offset))
+                {
+                    Some(due_timestamp) => due_timestamp,
+                    None => panic_with_error!(e, Error::InvalidSchedule),
+                };
+                // the last installment takes the rounding remainder
+                let amount: i128 = if i + 1 == params.number_of_installments {
+                    total_payback - amount * (number_of_installments - 1)
+                } else {
+                    amount
+                };
+                installments.push_back(Installment {
+                    due_timestamp,
+                    amount,
+                });
+            }
+            installments
+        }
+    }
+}
+
+// Validates the schedule against the whole payback and stores it.
+// Due timestamps must be strictly increasing and after the end of the
+// funding, installments must add up to the payback exactly.
+pub fn init_schedule(e: &Env, schedule: RepaymentSchedule, total_payback: i128, final_timestamp: u64) {
+    let installments: Vec<Installment> = build_installments(e, schedule, total_payback);
+    if installments.is_empty() {
+        panic_with_error!(e, Error::InvalidSchedule)
+    }
+
+    let mut total: i128 = 0;
+    let mut previous_due: u64 = final_timestamp;
+    for installment in installments.iter() {
+        if installment.amount <= 0 {
+            panic_with_error!(e, Error::InvalidSchedule)
+        }
+        if installment.due_timestamp <= previous_due {
+            panic_with_error!(e, Error::InvalidSchedule)
+        }
+        previous_due = installment.due_timestamp;
+        total += installment.amount;
+    }
+    if total != total_payback {
+        panic_with_error!(e, Error::InvalidSchedule)
+    }
+
+    write_schedule(e, &installments);
+}
+
+// Scales the stored installments to a smaller payback when the
+// project was funded below its target, the last installment
+// takes the rounding remainder
+pub fn scale_schedule(e: &Env, total_payback: i128) {
+    let installments: Vec<Installment> = read_schedule(e);
+    if installments.is_empty() {
+        return;
+    }
+    let mut scheduled: i128 = 0;
+    for installment in installments.iter() {
+        scheduled += installment.amount;
+    }
+
+    let mut scaled: Vec<Installment> = Vec::<Installment>::new(e);
+    let mut total: i128 = 0;
+    for (i, installment) in installments.iter().enumerate() {
+        let amount: i128 = if i as u32 + 1 == installments.len() {
+            total_payback - total
+        } else {
+            mul_div(e, installment.amount, total_payback, scheduled, Rounding::HalfEven)
+        };
+        total += amount;
+        scaled.push_back(Installment {
+            due_timestamp: installment.due_timestamp,
+            amount,
+        });
+    }
+    write_schedule(e, &scaled);
+}
+
+// Installments are paid in order, `repaid` is the total amount
+// returned by the borrower so far
+pub fn installment_infos(e: &Env, repaid: i128) -> Vec<InstallmentInfo> {
+    let now: u64 = e.ledger().timestamp();
+    let mut infos: Vec<InstallmentInfo> = Vec::<InstallmentInfo>::new(e);
+    let mut remaining: i128 = repaid;
+    let mut next_found: bool = false;
+    for installment in read_schedule(e).iter() {
+        let paid: i128 = remaining.clamp(0, installment.amount);
+        remaining -= paid;
+
+        let status: InstallmentStatus = if paid == installment.amount {
+            InstallmentStatus::Paid
+        } else if now > installment.due_timestamp {
+            InstallmentStatus::Overdue
+        } else if !next_found {
+            InstallmentStatus::Due
+        } else {
+            InstallmentStatus::Upcoming
+        };
+        if status != InstallmentStatus::Paid {
+            next_found = true;
+        }
+
+        infos.push_back(InstallmentInfo {
+            due_timestamp: installment.due_timestamp,
+            amount: installment.amount,
+            paid,
+            status,
+        });
+    }
+    infos
+}
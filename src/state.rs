@@ -0,0 +1,54 @@
+use crate::errors::Error;
+use crate::storage_types::{DataKey, ProjectState, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{panic_with_error, Env, Symbol};
+
+pub fn read_state(e: &Env) -> ProjectState {
+    let key = DataKey::ProjectState;
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(ProjectState::Created)
+}
+
+fn write_state(e: &Env, state: ProjectState) {
+    let key = DataKey::ProjectState;
+    e.storage().persistent().set(&key, &state);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn is_valid_transition(from: ProjectState, to: ProjectState) -> bool {
+    use ProjectState::*;
+    matches!(
+        (from, to),
+        (Created, Funding)
+            | (Created, Refunding)
+            | (Funding, Funded)
+            | (Funding, Refunding)
+            | (Funded, Disbursed)
+            | (Funded, Refunding)
+            | (Disbursed, Repaying)
+            | (Disbursed, Repaid)
+            | (Disbursed, Defaulted)
+            | (Repaying, Repaid)
+            | (Repaying, Defaulted)
+            | (Defaulted, Repaid)
+            | (Defaulted, Closed)
+            | (Repaid, Closed)
+            | (Refunding, Closed)
+    )
+}
+
+// Moves the project to the next state, panics on a transition
+// that is not part of the lifecycle
+pub fn transition(e: &Env, to: ProjectState) {
+    let from: ProjectState = read_state(e);
+    if !is_valid_transition(from, to) {
+        panic_with_error!(e, Error::InvalidStateTransition)
+    }
+    write_state(e, to);
+
+    e.events()
+        .publish((Symbol::new(e, "state_changed"),), (from, to));
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Vec};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
@@ -7,6 +7,13 @@ pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_I
 pub(crate) const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
 pub(crate) const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
+pub(crate) const REWARD_DENOM: i128 = 10000;
+// Highest reward rate a project can pay, in bps of the principal
+pub(crate) const MAX_REWARD_RATE: i128 = 10000;
+pub(crate) const DEFAULT_PROTOCOL_FEE: i128 = 1000;
+
+pub(crate) const MAX_PAGE_SIZE: u32 = 50;
+
 #[derive(Clone)]
 #[contracttype]
 pub struct AllowanceDataKey {
@@ -14,12 +21,20 @@ pub struct AllowanceDataKey {
     pub spender: Address,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct TrancheDataKey {
+    pub tranche: u32,
+    pub address: Address,
+}
+
 #[contracttype]
 pub struct AllowanceValue {
     pub amount: i128,
     pub expiration_ledger: u32,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct ProjectInfo {
     pub borrower: Address,
@@ -31,6 +46,386 @@ pub struct ProjectInfo {
     pub final_timestamp: u64,
     pub reward_rate: i128,
     pub treasury_address: Address,
+    // Per-lender limits, the concentration cap is in bps of the target amount
+    pub min_lend_amount: i128,
+    pub max_lend_amount: Option<i128>,
+    pub max_concentration: Option<i128>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Installment {
+    pub due_timestamp: u64,
+    pub amount: i128,
+}
+
+// Equal installments of the whole payback, every `period` seconds
+// starting from `first_due_timestamp`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmortizationParams {
+    pub first_due_timestamp: u64,
+    pub period: u64,
+    pub number_of_installments: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RepaymentSchedule {
+    Installments(Vec<Installment>),
+    Amortized(AmortizationParams),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum InstallmentStatus {
+    Paid,
+    Overdue,
+    Due,
+    Upcoming,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InstallmentInfo {
+    pub due_timestamp: u64,
+    pub amount: i128,
+    pub paid: i128,
+    pub status: InstallmentStatus,
+}
+
+// Holdings of a lender over all tranches, `share` is in bps of the total supply
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LenderPosition {
+    pub balance: i128,
+    pub claimed: i128,
+    pub available: i128,
+    pub share: i128,
+    pub first_lend_at: Option<u64>,
+}
+
+// Everything a project card shows, read at a single ledger. Durations
+// are the seconds until start_timestamp and final_timestamp, 0 once passed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProjectSummary {
+    pub state: ProjectState,
+    pub target_amount: i128,
+    pub raised_amount: i128,
+    pub remaining_to_target: i128,
+    pub total_return: i128,
+    pub fee_accumulated: i128,
+    pub outstanding_payback: i128,
+    pub number_of_lenders: u128,
+    pub seconds_to_start: u64,
+    pub seconds_to_end: u64,
+    pub collateral_status: CollateralStatus,
+}
+
+// Protocol fee in bps of the interest, the admin can change it
+// within [min_fee, max_fee] until the funding starts
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeeConfig {
+    pub protocol_fee: i128,
+    pub min_fee: i128,
+    pub max_fee: i128,
+}
+
+// Tranches are repaid in order, the first one is the most senior
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Tranche {
+    pub target_amount: i128,
+    pub reward_rate: i128,
+}
+
+// Return index of the tranche when the holder was last settled
+// and the returns earned until then that are not claimed yet
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReturnCheckpoint {
+    pub index: i128,
+    pub pending: i128,
+}
+
+// Flat applies reward_rate once to the principal, Simple and Compound
+// accrue `apr` (in bps per year) from the disbursement on
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum InterestMode {
+    Flat,
+    Simple,
+    Compound,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InterestConfig {
+    pub mode: InterestMode,
+    pub apr: i128,
+    // seconds, only used by Compound
+    pub compounding_period: u64,
+}
+
+// Outstanding principal and unpaid interest of an accruing loan
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Loan {
+    pub principal: i128,
+    pub interest: i128,
+    pub accrued_at: u64,
+}
+
+// Grace periods in seconds after which an unpaid loan can be declared
+// in default: after the due timestamp of an installment, or after the
+// maturity (last installment, or final_timestamp without a schedule)
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DefaultConfig {
+    pub installment_grace_period: u64,
+    pub maturity_grace_period: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CollateralStatus {
+    Missing,
+    Held,
+    Seized,
+    Released,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BorrowerClaimStatus {
+    Available,
+    AlreadyClaimed,
+    TargetNotReached,
+    NoCollateral,
+    Paused,
+    // refunding or closed without a disbursement
+    InvalidState,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum LendStatus {
+    Available,
+    Paused,
+    InvalidAmount,
+    NotStarted,
+    AlreadyFinished,
+    NotKycApproved,
+    InvalidTranche,
+    TargetOverreached,
+    BelowMinimum,
+    AboveMaximum,
+    ConcentrationExceeded,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum LenderClaimStatus {
+    Available,
+    Paused,
+    InvalidTranche,
+    NothingToClaim,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BorrowerReturnStatus {
+    Available,
+    Paused,
+    InvalidAmount,
+    InvalidState,
+    ReturnOverreached,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum KycStatus {
+    None,
+    Approved,
+    Expired,
+}
+
+// Lifecycle of the project:
+// Created -> Funding -> Funded -> Disbursed -> Repaying -> Repaid -> Closed
+// Funding that did not reach the target (or a cancelled project) goes
+// to Refunding and then to Closed once all lenders are refunded
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProjectState {
+    Created,
+    Funding,
+    Funded,
+    Disbursed,
+    Repaying,
+    Repaid,
+    Defaulted,
+    Refunding,
+    Closed,
+}
+
+// Roles the admin can hand out to any number of accounts,
+// while nobody holds a role the admin acts in it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Role {
+    // Project setup and funding parameters
+    Operator,
+    // Fees and rescuing tokens
+    Treasurer,
+    Pauser,
+    // Collateral transfers and the collateral vault
+    CollateralManager,
+    // KYC provider and allowlist mode
+    Compliance,
+}
+
+// LP balance or supply as of `timestamp`, voting power is
+// read from the last checkpoint before a proposal was created
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Checkpoint {
+    pub timestamp: u64,
+    pub amount: i128,
+}
+
+// Quorum in bps of the LP supply, threshold in bps of the votes cast
+// that have to be in favor, voting period in seconds
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GovernanceConfig {
+    pub quorum: i128,
+    pub threshold: i128,
+    pub voting_period: u64,
+}
+
+// Material changes to a project lenders have committed funds to
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProposalAction {
+    // New final_timestamp of the funding
+    ExtendDeadline(u64),
+    SetRewardRate(i128),
+    ReplaceBorrower(Address),
+    // New treasury_address receiving the protocol fee
+    SetTreasury(Address),
+    // Returns the collateral to the borrower before the loan is repaid
+    ReleaseCollateral,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProposalStatus {
+    Active,
+    Passed,
+    Rejected,
+    Executed,
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Proposal {
+    pub action: ProposalAction,
+    pub proposer: Address,
+    // Creation time, votes are weighted by the LP balance before it
+    pub snapshot: u64,
+    pub end_timestamp: u64,
+    pub supply: i128,
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub status: ProposalStatus,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CheckpointDataKey {
+    pub address: Address,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct VoteDataKey {
+    pub proposal_id: u32,
+    pub voter: Address,
+}
+
+// Entrypoints the Pauser role can stop one by one,
+// `Transfer` covers every LP transfer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PausableFunction {
+    Lend,
+    WithdrawPledge,
+    LenderClaim,
+    BorrowerClaim,
+    BorrowerReturn,
+    Transfer,
+}
+
+// A single ProjectInfo field with its value, `set_project_field` only accepts
+// the fields that can still change in the current phase of the project
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProjectField {
+    Borrower(Address),
+    LendTokenAddress(Address),
+    CollateralNftAddress(Address),
+    CollateralId(u128),
+    TargetAmount(i128),
+    StartTimestamp(u64),
+    FinalTimestamp(u64),
+    RewardRate(i128),
+    TreasuryAddress(Address),
+    MinLendAmount(i128),
+    MaxLendAmount(Option<i128>),
+    MaxConcentration(Option<i128>),
+}
+
+// Admin proposed by `propose_admin`, it can accept from `eta` on
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingAdmin {
+    pub admin: Address,
+    pub eta: u64,
+}
+
+// Sensitive admin actions, while a timelock delay is set they
+// have to be queued and can only be executed after the delay
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum AdminAction {
+    SetProjectField(ProjectField),
+    // token, to, amount
+    RescueTokens(Address, Address, i128),
+    Upgrade(BytesN<32>),
+    SetTimelockDelay(u64),
+    // to, amount
+    Mint(Address, i128),
+    // from, amount
+    Burn(Address, i128),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct QueuedAction {
+    pub action: AdminAction,
+    pub eta: u64,
+}
+
+// Versions reported by `version`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Version {
+    pub code_version: u32,
+    pub schema_version: u32,
 }
 
 #[derive(Clone)]
@@ -46,10 +441,73 @@ pub enum DataKey {
     NumberOfLenders,
     LenderIndex(Address),
     LenderAddress(u128),
-    ClaimAvailable,
-    ClaimedBalance(Address),
+    ReturnCheckpoint(TrancheDataKey),
     TotalReturn,
     FeeAccumulated,
-    TargetNotReached,
-    BorrowerClaimed,
+    ProjectState,
+    DisbursedAt,
+    Schedule,
+    DefaultConfig,
+    DefaultedAt,
+    CollateralVault,
+    CollateralStatus,
+    CollateralDepositor,
+    FeeConfig,
+    MinAmount,
+    PledgeLockPeriod,
+    KycProvider,
+    KycRequired,
+    KycApproval(Address),
+    InterestConfig,
+    Loan,
+    Tranches,
+    TrancheBalance(TrancheDataKey),
+    TrancheSupply(u32),
+    TrancheRaised(u32),
+    TrancheRepaid(u32),
+    TrancheReturn(u32),
+    TranchePrincipalRepaid(u32),
+    TranchePaid(u32),
+    ReturnIndex(u32),
+    TranchePending(u32),
+    Dust(u32),
+    Claimed(Address),
+    FirstLendAt(Address),
+    SchemaVersion,
+    PendingAdmin,
+    TimelockDelay,
+    NextActionId,
+    QueuedAction(u32),
+    Paused(PausableFunction),
+    MaxPauseDuration,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RoleHolderDataKey {
+    pub role: Role,
+    pub account: Address,
+}
+
+// Keys of the role memberships
+#[derive(Clone)]
+#[contracttype]
+pub enum RoleDataKey {
+    Holder(RoleHolderDataKey),
+    HolderCount(Role),
+}
+
+// Keys of the proposals and the balance snapshots they are voted with
+#[derive(Clone)]
+#[contracttype]
+pub enum GovernanceDataKey {
+    Config,
+    // number of checkpoints, each one is stored under its own index
+    BalanceCheckpointCount(Address),
+    BalanceCheckpoint(CheckpointDataKey),
+    SupplyCheckpointCount,
+    SupplyCheckpoint(u32),
+    NextProposalId,
+    Proposal(u32),
+    Vote(VoteDataKey),
 }
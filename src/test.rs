@@ -2,9 +2,19 @@
 extern crate std;
 use std::println;
 
-use crate::{contract::EnerDAOToken, contract::EnerDAOTokenClient, storage_types::ProjectInfo};
+use crate::{
+    contract::EnerDAOToken, contract::EnerDAOTokenClient,
+    errors::{DefaultError, Error, GovernanceError, ProjectInfoError},
+    storage_types::{
+        AmortizationParams, BorrowerClaimStatus, BorrowerReturnStatus, CollateralStatus, DataKey, DefaultConfig,
+        FeeConfig, GovernanceConfig, GovernanceDataKey, Installment, InstallmentStatus, InterestConfig, InterestMode, KycStatus,
+        LendStatus, LenderClaimStatus, LenderPosition, ProjectInfo, ProjectState, PausableFunction, ProjectField, ProjectSummary, ProposalAction, ProposalStatus, RepaymentSchedule, Role,
+        Tranche, Version,
+    },
+    upgrade::{LegacyDataKey, LegacyProjectInfo},
+};
 use soroban_sdk::{
-    ledger, symbol_short, testutils::{Address as _, Ledger, LedgerInfo}, token, vec, Address, Vec, Env, IntoVal, String
+    ledger, symbol_short, testutils::{Address as _, Events, Ledger, LedgerInfo}, token, vec, Address, Vec, Env, IntoVal, String, Symbol
 };
 
 mod token_contract {
@@ -40,6 +50,42 @@ fn create_token<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, TokenAdminCli
     )
 }
 
+fn advance_time(e: &Env, seconds: u64) {
+    let mut ledger_info: LedgerInfo = e.ledger().get();
+    ledger_info.timestamp += seconds;
+    e.ledger().set(ledger_info);
+}
+
+// Project with collateral id 0 and a 10% reward rate,
+// the funding opens in 100 seconds and ends in 1000
+fn create_project<'a>(
+    e: &Env,
+    admin: &Address,
+    borrower: &Address,
+    eurc_token: &Address,
+    nft: &Address,
+    target_amount: i128,
+    schedule: Option<RepaymentSchedule>,
+) -> EnerDAOTokenClient<'a> {
+    let current_timestamp: u64 = e.ledger().timestamp();
+
+    let contract = EnerDAOTokenClient::new(e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(admin, &7, &"LP EnerDAO".into_val(e), &"LPE".into_val(e));
+    contract.init_project(
+        borrower,
+        eurc_token,
+        nft,
+        &0,
+        &target_amount,
+        &(current_timestamp + 100_u64),
+        &(current_timestamp + 1000_u64),
+        &1000,
+        admin,
+        &schedule,
+    );
+    contract
+}
+
 #[test]
 fn test_lend() {
     // Here we test eurc token lend to the contract
@@ -68,6 +114,9 @@ fn test_lend() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 0,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
@@ -83,6 +132,7 @@ fn test_lend() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
 
     assert_eq!(contract.total_supply(), 0);
@@ -90,21 +140,21 @@ fn test_lend() {
     assert_eq!(eurc_token.balance(&lender), 3000_0000000i128);
     assert_eq!(contract.number_of_lenders(), 0);
 
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     assert_eq!(contract.total_supply(), 1000_0000000i128);
     assert_eq!(contract.balance(&lender), 1000_0000000i128);
     assert_eq!(eurc_token.balance(&lender), 2000_0000000i128);
     assert_eq!(contract.number_of_lenders(), 1);
 
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     assert_eq!(contract.total_supply(), 2000_0000000i128);
     assert_eq!(contract.balance(&lender), 2000_0000000i128);
     assert_eq!(eurc_token.balance(&lender), 1000_0000000i128);
     assert_eq!(contract.number_of_lenders(), 1);
 
-    contract.lend(&lender_2, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
 
     assert_eq!(contract.total_supply(), 3000_0000000i128);
     assert_eq!(contract.balance(&lender), 2000_0000000i128);
@@ -113,7 +163,7 @@ fn test_lend() {
     assert_eq!(eurc_token.balance(&lender_2), 0);
     assert_eq!(contract.number_of_lenders(), 2);
 
-    assert_eq!(contract.get_lenders(), vec![&e, lender, lender_2]);
+    assert_eq!(contract.get_lenders(&0, &10), vec![&e, lender, lender_2]);
 }
 
 #[test]
@@ -148,6 +198,9 @@ fn test_borrower_return() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 1000,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
@@ -163,6 +216,7 @@ fn test_borrower_return() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
 
     assert_eq!(contract.total_supply(), 0);
@@ -170,14 +224,14 @@ fn test_borrower_return() {
     assert_eq!(eurc_token.balance(&lender), 1000_0000000i128);
     assert_eq!(contract.number_of_lenders(), 0);
 
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     assert_eq!(contract.total_supply(), 1000_0000000i128);
     assert_eq!(contract.balance(&lender), 1000_0000000i128);
     assert_eq!(eurc_token.balance(&lender), 0);
     assert_eq!(contract.number_of_lenders(), 1);
 
-    contract.lend(&lender_2, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
 
     assert_eq!(eurc_token.balance(&contract.address), 2000_0000000i128);
     assert_eq!(contract.total_supply(), 2000_0000000i128);
@@ -193,7 +247,7 @@ fn test_borrower_return() {
 
     assert_eq!(
         contract.borrower_claim_status(),
-        String::from_str(&e, "NoCollateral")
+        BorrowerClaimStatus::NoCollateral
     );
 
     nft.mint(
@@ -205,12 +259,12 @@ fn test_borrower_return() {
     assert_eq!(eurc_token.balance(&borrower), 0);
     assert_eq!(
         contract.borrower_claim_status(),
-        String::from_str(&e, "Available")
+        BorrowerClaimStatus::Available
     );
     contract.borrower_claim();
     assert_eq!(
         contract.borrower_claim_status(),
-        String::from_str(&e, "AlreadyClaimed")
+        BorrowerClaimStatus::AlreadyClaimed
     );
     // assert_eq!(eurc_token.balance(&borrower), 2000_0000000i128);
     // assert_eq!(eurc_token.balance(&contract.address), 0);
@@ -219,9 +273,9 @@ fn test_borrower_return() {
 
     contract.borrower_return(&borrower, &1100_0000000i128);
     assert_eq!(contract.borrower_to_payback(), 1100_0000000i128);
-    assert_eq!(contract.lender_available_to_claim(&lender), 545_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 545_0000000i128);
     assert_eq!(
-        contract.lender_available_to_claim(&lender_2),
+        contract.lender_available_to_claim(&lender_2, &0),
         545_0000000i128
     );
 
@@ -229,11 +283,11 @@ fn test_borrower_return() {
     contract.borrower_return(&borrower, &1100_0000000i128);
     assert_eq!(contract.borrower_to_payback(), 0);
     assert_eq!(
-        contract.lender_available_to_claim(&lender),
+        contract.lender_available_to_claim(&lender, &0),
         1090_0000000i128
     );
     assert_eq!(
-        contract.lender_available_to_claim(&lender_2),
+        contract.lender_available_to_claim(&lender_2, &0),
         1090_0000000i128
     );
 
@@ -271,6 +325,9 @@ fn test_rounding() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 1000,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
@@ -286,11 +343,12 @@ fn test_rounding() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
 
-    contract.lend(&lender, &1000_0000000i128);
-    contract.lend(&lender_2, &1000_0000000i128);
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     let mut current_info: LedgerInfo = e.ledger().get();
     current_info.timestamp = current_timestamp + 1001_u64;
@@ -310,14 +368,14 @@ fn test_rounding() {
 
     contract.borrower_return(&borrower, &1000_0000000i128);
     assert_eq!(contract.borrower_to_payback(), 2300_0000000i128);
-    assert_eq!(contract.lender_available_to_claim(&lender), 660_6060606);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 330_3030303);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 660_6060606);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 330_3030303);
     assert_eq!(eurc_token.balance(&contract.address), 990_9090910); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 9_0909090); // protocol fee
 
-    contract.lender_claim(&lender_2);
-    assert_eq!(contract.lender_available_to_claim(&lender), 660_6060606);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 0);
+    contract.lender_claim(&lender_2, &0);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 660_6060606);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 0);
 }
 
 #[test]
@@ -352,6 +410,9 @@ fn test_rounding_2() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 1000,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
@@ -367,9 +428,10 @@ fn test_rounding_2() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
 
-    contract.lend(&lender, &2000_0000000i128);
+    contract.lend(&lender, &0, &2000_0000000i128);
 
     let mut current_info: LedgerInfo = e.ledger().get();
     current_info.timestamp = current_timestamp + 1001_u64;
@@ -386,40 +448,40 @@ fn test_rounding_2() {
     contract.borrower_return(&borrower, &200_0000000i128);
     assert_eq!(eurc_token.balance(&contract.address), 198_1818182); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 1_8181818); // protocol fee
-    assert_eq!(contract.lender_available_to_claim(&lender), 198_1818182);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 198_1818182);
 
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
 
     contract.borrower_return(&borrower, &200_0000000i128);
     assert_eq!(eurc_token.balance(&contract.address), 1981818182); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 1_8181818*2); // protocol fee
-    assert_eq!(contract.lender_available_to_claim(&lender), 198_1818182);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 198_1818182);
 
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
 
     contract.borrower_return(&borrower, &200_0000000i128);
     assert_eq!(eurc_token.balance(&contract.address), 198_1818182); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 1_8181818*3); // protocol fee
-    assert_eq!(contract.lender_available_to_claim(&lender), 198_1818182);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 198_1818182);
 
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
 
     contract.borrower_return(&borrower, &200_0000000i128);
     assert_eq!(eurc_token.balance(&contract.address), 198_1818182); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 1_8181818*4); // protocol fee
-    assert_eq!(contract.lender_available_to_claim(&lender), 198_1818182);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 198_1818182);
 
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
 
     contract.borrower_return(&borrower, &200_0000000i128);
     assert_eq!(eurc_token.balance(&contract.address), 198_1818182); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 1_8181818*5); // protocol fee
-    assert_eq!(contract.lender_available_to_claim(&lender), 198_1818182);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 198_1818182);
 
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
     eurc_token.mint(&borrower, &200_0000000i128);
     contract.borrower_return(&borrower, &1200_0000000i128);
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
 
     assert_eq!(contract.balance(&lender), 0);
 }
@@ -454,6 +516,9 @@ fn test_failed_target_amount() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 1000,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
@@ -469,15 +534,16 @@ fn test_failed_target_amount() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
 
-    contract.lend(&lender, &1000_0000000i128);
-    contract.lend(&lender_2, &1000_0000000i128);
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     assert_eq!(
         contract.borrower_claim_status(),
-        String::from_str(&e, "TargetNotReached")
+        BorrowerClaimStatus::TargetNotReached
     );
 
     // contract.set_lender_claim_available(&true, &true);
@@ -488,26 +554,26 @@ fn test_failed_target_amount() {
     e.ledger().set(current_info);
 
     assert_eq!(
-        contract.lender_available_to_claim(&lender),
+        contract.lender_available_to_claim(&lender, &0),
         2000_0000000i128
     );
     assert_eq!(
-        contract.lender_available_to_claim(&lender_2),
+        contract.lender_available_to_claim(&lender_2, &0),
         1000_0000000i128
     );
     assert_eq!(contract.balance(&lender), 2000_0000000i128);
     assert_eq!(contract.balance(&lender_2), 1000_0000000i128);
 
-    contract.lender_claim(&lender_2);
+    contract.lender_claim(&lender_2, &0);
     assert_eq!(
-        contract.lender_available_to_claim(&lender),
+        contract.lender_available_to_claim(&lender, &0),
         2000_0000000i128
     );
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 0);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 0);
 
-    contract.lender_claim(&lender);
-    assert_eq!(contract.lender_available_to_claim(&lender), 0);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 0);
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 0);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 0);
     assert_eq!(eurc_token.balance(&lender), 2000_0000000i128);
     assert_eq!(eurc_token.balance(&lender_2), 1000_0000000i128);
     assert_eq!(contract.balance(&lender), 0);
@@ -546,6 +612,9 @@ fn test_transfer() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 1000,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
@@ -561,11 +630,12 @@ fn test_transfer() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
 
-    contract.lend(&lender, &1000_0000000i128);
-    contract.lend(&lender_2, &1000_0000000i128);
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     let mut current_info: LedgerInfo = e.ledger().get();
     current_info.timestamp = current_timestamp + 1001_u64;
@@ -582,31 +652,36 @@ fn test_transfer() {
     assert_eq!(eurc_token.balance(&contract.address), 0);
 
     contract.borrower_return(&borrower, &1000_0000000i128);
-    assert_eq!(contract.lender_available_to_claim(&lender), 660_6060606);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 330_3030303);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 660_6060606);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 330_3030303);
     assert_eq!(eurc_token.balance(&contract.address), 990_9090910); // return - protocol fee
     assert_eq!(eurc_token.balance(&admin), 9_0909090); // protocol fee
 
+    // returns earned before the transfer stay with the sender
     contract.transfer(&lender, &lender_2, &1000_0000000i128);
-    assert_eq!(contract.lender_available_to_claim(&lender), 330_3030303);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 660_6060606);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 660_6060606);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 330_3030303);
 
     contract.borrower_return(&borrower, &1000_0000000i128);
     assert_eq!(eurc_token.balance(&admin), 2*9_0909090); // protocol fee
-    assert_eq!(contract.lender_available_to_claim(&lender), 2*330_3030303);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 2*660_6060606 + 1);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 990_9090909);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 990_9090909);
 
-    contract.lender_claim(&lender);
-    assert_eq!(contract.balance(&lender), 393_9393940);
-    contract.transfer(&lender, &lender_2, &393_9393940);
-    assert_eq!(contract.lender_available_to_claim(&lender), 0);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 2*660_6060606 + 2);
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.balance(&lender), 1000_0000000i128);
+    contract.transfer(&lender, &lender_2, &1000_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 0);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 990_9090909);
 
     eurc_token.mint(&borrower, &300_0000000i128);
     contract.borrower_return(&borrower, &1300_0000000i128);
-    assert_eq!(contract.lender_available_to_claim(&lender), 0);
-    assert_eq!(contract.lender_available_to_claim(&lender_2), 3270_0000000 - 2*330_3030303 + 2);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 0);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 2279_0909091);
 
+    contract.lender_claim(&lender_2, &0);
+    assert_eq!(contract.total_supply(), 0);
+    assert_eq!(eurc_token.balance(&lender) + eurc_token.balance(&lender_2), 3270_0000000);
+    assert_eq!(contract.dust(), 2);
 }
 
 #[test]
@@ -636,6 +711,9 @@ fn test_budget() {
         final_timestamp: current_timestamp + 1000_u64,
         reward_rate: 0,
         treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
     };
 
     mod wasm_contract {
@@ -646,8 +724,14 @@ fn test_budget() {
     let contract_id = &e.register_contract_wasm(None, wasm_contract::WASM);
     let contract = EnerDAOTokenClient::new(&e, &contract_id);
 
+    // every call gets the default budget of a transaction, the calls
+    // have to stay below a fixed share of it so that growth is noticed
+    const CALL_CPU_LIMIT: u64 = 60_000_000;
+    e.budget().reset_default();
     contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    assert!(e.budget().cpu_instruction_cost() < CALL_CPU_LIMIT);
 
+    e.budget().reset_default();
     contract.init_project(
         &project_info.borrower,
         &project_info.lend_token_address,
@@ -658,7 +742,9 @@ fn test_budget() {
         &project_info.final_timestamp,
         &project_info.reward_rate,
         &project_info.treasury_address,
+        &None,
     );
+    assert!(e.budget().cpu_instruction_cost() < CALL_CPU_LIMIT);
 
     e.budget().reset_unlimited();
     let mut lenders: Vec<Address> = Vec::<Address>::new(&e);
@@ -666,11 +752,11 @@ fn test_budget() {
         let lender = Address::generate(&e);
         lenders.push_back(lender.clone());
         eurc_token.mint(&lender, &1000_0000000i128);
-        contract.lend(&lender, &1000_0000000i128);
+        contract.lend(&lender, &0, &1000_0000000i128);
     }
 
     eurc_token.mint(&lender, &1000_0000000i128);
-    contract.lend(&lender, &1000_0000000i128);
+    contract.lend(&lender, &0, &1000_0000000i128);
 
     assert_eq!(eurc_token.balance(&contract.address), 10_000_0000000i128);
 
@@ -692,11 +778,2327 @@ fn test_budget() {
         "      Borrower return: {:?}",
         e.budget().cpu_instruction_cost()
     );
+    assert!(e.budget().cpu_instruction_cost() < CALL_CPU_LIMIT);
 
     e.budget().reset_unlimited();
-    contract.lender_claim(&lender);
+    contract.lender_claim(&lender, &0);
     println!(
         "        Lender claim: {:?}",
         e.budget().cpu_instruction_cost()
     );
+    assert!(e.budget().cpu_instruction_cost() < CALL_CPU_LIMIT);
+}
+
+#[test]
+fn test_project_state() {
+    // Here we test the project lifecycle from creation to closing
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&borrower, &100_0000000i128);
+
+    let nft = create_nft(&e, &admin);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let project_info = ProjectInfo {
+        borrower: borrower.clone(),
+        lend_token_address: eurc_token.address.clone(),
+        collateral_nft_address: nft.address.clone(),
+        collateral_id: 1,
+        target_amount: 1000_0000000i128,
+        start_timestamp: current_timestamp + 100_u64,
+        final_timestamp: current_timestamp + 1000_u64,
+        reward_rate: 1000,
+        treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
+    };
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+
+    contract.init_project(
+        &project_info.borrower,
+        &project_info.lend_token_address,
+        &project_info.collateral_nft_address,
+        &project_info.collateral_id,
+        &project_info.target_amount,
+        &project_info.start_timestamp,
+        &project_info.final_timestamp,
+        &project_info.reward_rate,
+        &project_info.treasury_address,
+        &None,
+    );
+
+    assert_eq!(contract.project_state(), ProjectState::Created);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 100_u64;
+    e.ledger().set(current_info);
+    assert_eq!(contract.project_state(), ProjectState::Funding);
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Funded);
+
+    nft.mint(&contract.address, &1, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    assert_eq!(contract.project_state(), ProjectState::Disbursed);
+    assert!(!contract.is_lender_claim_available());
+
+    contract.borrower_return(&borrower, &500_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaying);
+    assert!(contract.is_lender_claim_available());
+
+    contract.borrower_return(&borrower, &600_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.total_supply(), 0);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "state_changed"),).into_val(&e),
+                (ProjectState::Repaid, ProjectState::Closed).into_val(&e)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_cancel_project() {
+    // Here we test that a cancelled project refunds lenders
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let project_info = ProjectInfo {
+        borrower: borrower.clone(),
+        lend_token_address: eurc_token.address.clone(),
+        collateral_nft_address: eurc_token.address.clone(),
+        collateral_id: 0,
+        target_amount: 3000_0000000i128,
+        start_timestamp: current_timestamp,
+        final_timestamp: current_timestamp + 1000_u64,
+        reward_rate: 1000,
+        treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
+    };
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+
+    contract.init_project(
+        &project_info.borrower,
+        &project_info.lend_token_address,
+        &project_info.collateral_nft_address,
+        &project_info.collateral_id,
+        &project_info.target_amount,
+        &project_info.start_timestamp,
+        &project_info.final_timestamp,
+        &project_info.reward_rate,
+        &project_info.treasury_address,
+        &None,
+    );
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Funding);
+
+    contract.cancel_project(&admin);
+    assert_eq!(contract.project_state(), ProjectState::Refunding);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "state_changed"),).into_val(&e),
+                (ProjectState::Funding, ProjectState::Refunding).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(
+        contract.borrower_claim_status(),
+        BorrowerClaimStatus::InvalidState
+    );
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 1000_0000000i128);
+
+    contract.lender_claim(&lender, &0);
+    assert_eq!(eurc_token.balance(&lender), 1000_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #102)")]
+fn test_lend_not_started() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    contract.init_project(
+        &admin,
+        &eurc_token.address,
+        &eurc_token.address,
+        &0,
+        &1000_0000000i128,
+        &(current_timestamp + 100_u64),
+        &(current_timestamp + 1000_u64),
+        &0,
+        &admin,
+        &None,
+    );
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #111)")]
+fn test_return_before_claim() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&borrower, &1000_0000000i128);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    contract.init_project(
+        &borrower,
+        &eurc_token.address,
+        &eurc_token.address,
+        &0,
+        &1000_0000000i128,
+        &current_timestamp,
+        &(current_timestamp + 1000_u64),
+        &0,
+        &admin,
+        &None,
+    );
+
+    contract.borrower_return(&borrower, &100_0000000i128);
+}
+
+#[test]
+fn test_schedule() {
+    // Here we test tracking of paid, due and overdue installments
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&borrower, &100_0000000i128);
+
+    let nft = create_nft(&e, &admin);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let project_info = ProjectInfo {
+        borrower: borrower.clone(),
+        lend_token_address: eurc_token.address.clone(),
+        collateral_nft_address: nft.address.clone(),
+        collateral_id: 1,
+        target_amount: 1000_0000000i128,
+        start_timestamp: current_timestamp,
+        final_timestamp: current_timestamp + 1000_u64,
+        reward_rate: 1000,
+        treasury_address: admin.clone(),
+        min_lend_amount: 0,
+        max_lend_amount: None,
+        max_concentration: None,
+    };
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+
+    contract.init_project(
+        &project_info.borrower,
+        &project_info.lend_token_address,
+        &project_info.collateral_nft_address,
+        &project_info.collateral_id,
+        &project_info.target_amount,
+        &project_info.start_timestamp,
+        &project_info.final_timestamp,
+        &project_info.reward_rate,
+        &project_info.treasury_address,
+        &Some(RepaymentSchedule::Installments(vec![
+            &e,
+            Installment {
+                due_timestamp: current_timestamp + 2000_u64,
+                amount: 500_0000000i128,
+            },
+            Installment {
+                due_timestamp: current_timestamp + 3000_u64,
+                amount: 600_0000000i128,
+            },
+        ])),
+    );
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &1, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    let schedule = contract.schedule();
+    assert_eq!(schedule.len(), 2);
+    assert_eq!(schedule.get(0).unwrap().status, InstallmentStatus::Due);
+    assert_eq!(schedule.get(1).unwrap().status, InstallmentStatus::Upcoming);
+    assert_eq!(
+        contract.next_installment().unwrap().due_timestamp,
+        current_timestamp + 2000_u64
+    );
+
+    contract.borrower_return(&borrower, &300_0000000i128);
+    assert_eq!(contract.next_installment().unwrap().paid, 300_0000000i128);
+    assert_eq!(contract.overdue_amount(), 0);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 2500_u64;
+    e.ledger().set(current_info);
+
+    assert_eq!(
+        contract.next_installment().unwrap().status,
+        InstallmentStatus::Overdue
+    );
+    assert_eq!(contract.overdue_amount(), 200_0000000i128);
+
+    contract.borrower_return(&borrower, &300_0000000i128);
+    let schedule = contract.schedule();
+    assert_eq!(schedule.get(0).unwrap().status, InstallmentStatus::Paid);
+    assert_eq!(schedule.get(1).unwrap().status, InstallmentStatus::Due);
+    assert_eq!(schedule.get(1).unwrap().paid, 100_0000000i128);
+    assert_eq!(contract.overdue_amount(), 0);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 3001_u64;
+    e.ledger().set(current_info);
+    assert_eq!(contract.overdue_amount(), 500_0000000i128);
+
+    contract.borrower_return(&borrower, &500_0000000i128);
+    assert_eq!(contract.overdue_amount(), 0);
+    assert_eq!(contract.next_installment(), None);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+}
+
+#[test]
+fn test_amortized_schedule() {
+    // Here we test generation of equal installments
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    contract.init_project(
+        &borrower,
+        &eurc_token.address,
+        &eurc_token.address,
+        &0,
+        &1000_0000000i128,
+        &current_timestamp,
+        &(current_timestamp + 1000_u64),
+        &1000,
+        &admin,
+        &Some(RepaymentSchedule::Amortized(AmortizationParams {
+            first_due_timestamp: current_timestamp + 2000_u64,
+            period: 1000,
+            number_of_installments: 3,
+        })),
+    );
+
+    let schedule = contract.schedule();
+    assert_eq!(schedule.len(), 3);
+    assert_eq!(schedule.get(0).unwrap().amount, 366_6666666i128);
+    assert_eq!(schedule.get(1).unwrap().amount, 366_6666666i128);
+    assert_eq!(schedule.get(2).unwrap().amount, 366_6666668i128);
+    assert_eq!(schedule.get(1).unwrap().due_timestamp, current_timestamp + 3000_u64);
+    assert_eq!(schedule.get(2).unwrap().due_timestamp, current_timestamp + 4000_u64);
+}
+
+#[test]
+fn test_schedule_due_timestamps() {
+    // Here we test that installments are due after the end of the funding and the generated
+    // due timestamps do not overflow
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    let init_project = |schedule: RepaymentSchedule| {
+        contract.try_init_project(
+            &borrower,
+            &eurc_token.address,
+            &eurc_token.address,
+            &0,
+            &1000_0000000i128,
+            &current_timestamp,
+            &(current_timestamp + 1000_u64),
+            &1000,
+            &admin,
+            &Some(schedule),
+        )
+    };
+    assert_eq!(
+        init_project(RepaymentSchedule::Amortized(AmortizationParams {
+            first_due_timestamp: current_timestamp + 2000_u64,
+            period: u64::MAX / 2,
+            number_of_installments: 3,
+        })),
+        Err(Ok(Error::InvalidSchedule.into()))
+    );
+    assert_eq!(
+        init_project(RepaymentSchedule::Installments(vec![
+            &e,
+            Installment {
+                due_timestamp: current_timestamp + 1000_u64,
+                amount: 1100_0000000i128,
+            },
+        ])),
+        Err(Ok(Error::InvalidSchedule.into()))
+    );
+
+    init_project(RepaymentSchedule::Installments(vec![
+        &e,
+        Installment {
+            due_timestamp: current_timestamp + 2000_u64,
+            amount: 1100_0000000i128,
+        },
+    ]))
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::FinalTimestamp(current_timestamp + 2000_u64)),
+        Err(Ok(Error::InvalidSchedule.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #113)")]
+fn test_schedule_not_matching_payback() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    contract.init_project(
+        &borrower,
+        &eurc_token.address,
+        &eurc_token.address,
+        &0,
+        &1000_0000000i128,
+        &current_timestamp,
+        &(current_timestamp + 1000_u64),
+        &1000,
+        &admin,
+        &Some(RepaymentSchedule::Installments(vec![
+            &e,
+            Installment {
+                due_timestamp: current_timestamp + 2000_u64,
+                amount: 1000_0000000i128,
+            },
+        ])),
+    );
+}
+
+#[test]
+fn test_default_and_seize_collateral() {
+    // Here we test that a missed installment leads to collateral seizure
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let vault = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let current_timestamp: u64 = e.ledger().timestamp();
+
+    let contract = create_project(
+        &e,
+        &admin,
+        &borrower,
+        &eurc_token.address,
+        &nft.address,
+        1000_0000000i128,
+        Some(RepaymentSchedule::Installments(vec![
+            &e,
+            Installment {
+                due_timestamp: current_timestamp + 2000_u64,
+                amount: 500_0000000i128,
+            },
+            Installment {
+                due_timestamp: current_timestamp + 3000_u64,
+                amount: 600_0000000i128,
+            },
+        ])),
+    );
+    contract.set_default_config(&admin, &DefaultConfig {
+        installment_grace_period: 500,
+        maturity_grace_period: 1000,
+    });
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    contract.set_collateral_vault(&admin, &vault);
+    assert_eq!(contract.collateral_status(), CollateralStatus::Held);
+
+    contract.borrower_return(&borrower, &300_0000000i128);
+
+    // overdue, but still in the grace period
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 2500_u64;
+    e.ledger().set(current_info);
+    assert!(!contract.is_default_due());
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 2501_u64;
+    e.ledger().set(current_info);
+    assert!(contract.is_default_due());
+
+    contract.check_default();
+    assert_eq!(contract.project_state(), ProjectState::Defaulted);
+    // lenders can still claim what has been returned
+    assert!(contract.is_lender_claim_available());
+
+    contract.seize_collateral();
+    assert_eq!(nft.owner_of(&0), vault);
+    assert_eq!(contract.collateral_status(), CollateralStatus::Seized);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "collateral_seized"), vault.clone()).into_val(&e),
+                0_u128.into_val(&e)
+            )
+        ]
+    );
+    // the seized collateral can not be granted a second time
+    assert_eq!(
+        contract.try_grant_nft(&admin, &admin),
+        Err(Ok(Error::NoCollateral.into()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #114)")]
+fn test_check_default_on_track() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let current_timestamp: u64 = e.ledger().timestamp();
+
+    let contract = create_project(
+        &e,
+        &admin,
+        &borrower,
+        &eurc_token.address,
+        &nft.address,
+        1000_0000000i128,
+        Some(RepaymentSchedule::Installments(vec![
+            &e,
+            Installment {
+                due_timestamp: current_timestamp + 2000_u64,
+                amount: 500_0000000i128,
+            },
+            Installment {
+                due_timestamp: current_timestamp + 3000_u64,
+                amount: 600_0000000i128,
+            },
+        ])),
+    );
+    assert_eq!(
+        contract.try_set_default_config(&admin, &DefaultConfig {
+            installment_grace_period: 0,
+            maturity_grace_period: u64::MAX,
+        }),
+        Err(Ok(DefaultError::InvalidGracePeriod.into()))
+    );
+    contract.set_default_config(&admin, &DefaultConfig {
+        installment_grace_period: 0,
+        maturity_grace_period: 0,
+    });
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    assert_eq!(
+        contract.try_set_default_config(&admin, &DefaultConfig {
+            installment_grace_period: 1000,
+            maturity_grace_period: 1000,
+        }),
+        Err(Ok(Error::FundingStarted.into()))
+    );
+
+    // first installment is paid, the second one is not due yet
+    contract.borrower_return(&borrower, &500_0000000i128);
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 2999_u64;
+    e.ledger().set(current_info);
+
+    contract.check_default();
+}
+
+#[test]
+fn test_release_collateral() {
+    // Here we test that the collateral goes back to the borrower after repayment
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&borrower, &100_0000000i128);
+
+    let contract = create_project(
+        &e,
+        &admin,
+        &borrower,
+        &eurc_token.address,
+        &nft.address,
+        1000_0000000i128,
+        None,
+    );
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+
+    // the final return releases the collateral on its own
+    assert_eq!(nft.owner_of(&0), borrower);
+    assert_eq!(contract.collateral_status(), CollateralStatus::Released);
+    assert_eq!(
+        contract.try_release_collateral(),
+        Err(Ok(Error::NoCollateral.into()))
+    );
+}
+
+#[test]
+fn test_deposit_collateral() {
+    // Here we test that the borrower deposits the collateral and gets it back after repayment
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &2000_0000000i128);
+    eurc_token.mint(&borrower, &200_0000000i128);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    nft.mint(&borrower, &0, &String::from_str(&e, "https://uri.com"));
+    assert_eq!(contract.collateral_status(), CollateralStatus::Missing);
+
+    assert_eq!(
+        contract.try_deposit_collateral(&stranger),
+        Err(Ok(Error::NotAllowed.into()))
+    );
+
+    contract.deposit_collateral(&borrower);
+    assert_eq!(e.auths()[0].0, borrower);
+    assert_eq!(nft.owner_of(&0), contract.address);
+    assert_eq!(contract.collateral_depositor(), Some(borrower.clone()));
+    assert_eq!(contract.collateral_status(), CollateralStatus::Held);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "collateral_deposited"), borrower.clone()).into_val(&e),
+                0_u128.into_val(&e)
+            ),
+        ]
+    );
+    assert_eq!(
+        contract.try_deposit_collateral(&borrower),
+        Err(Ok(Error::CollateralAlreadyDeposited.into()))
+    );
+    // the deposited collateral and its depositor can no longer change
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::CollateralId(1)),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::Borrower(stranger.clone())),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+
+    // grant_nft is only for defaulted loans
+    assert_eq!(
+        contract.try_grant_nft(&admin, &admin),
+        Err(Ok(Error::NotDefaulted.into()))
+    );
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 100_u64;
+    e.ledger().set(current_info);
+    contract.lend(&lender, &0, &2000_0000000i128);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 1001_u64;
+    e.ledger().set(current_info);
+    contract.borrower_claim();
+
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(nft.owner_of(&0), contract.address);
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+    assert_eq!(nft.owner_of(&0), borrower);
+    assert_eq!(contract.collateral_status(), CollateralStatus::Released);
+}
+
+#[test]
+fn test_protocol_fee() {
+    // Here we test that the fee math uses the per-project protocol fee
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &1000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    assert_eq!(contract.fee_config().protocol_fee, 1000);
+
+    contract.set_fee_bounds(&admin, &500, &2500);
+    contract.set_protocol_fee(&admin, &2000);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "fee_changed"),).into_val(&e),
+                (1000_i128, 2000_i128).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(
+        contract.fee_config(),
+        FeeConfig { protocol_fee: 2000, min_fee: 500, max_fee: 2500 }
+    );
+
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(eurc_token.balance(&admin), 20_0000000i128); // protocol fee
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 540_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 540_0000000i128);
+
+    contract.lender_claim(&lender, &0);
+    assert_eq!(eurc_token.balance(&lender), 540_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #116)")]
+fn test_protocol_fee_frozen() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.set_protocol_fee(&admin, &2000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #117)")]
+fn test_protocol_fee_out_of_bounds() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_fee_bounds(&admin, &0, &1500);
+    contract.set_protocol_fee(&admin, &2000);
+}
+
+#[test]
+fn test_soft_cap() {
+    // Here we test a project funded between the soft cap and the target
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &500_0000000i128);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    contract.init_project(
+        &borrower,
+        &eurc_token.address,
+        &nft.address,
+        &0,
+        &2000_0000000i128,
+        &(current_timestamp + 100_u64),
+        &(current_timestamp + 1000_u64),
+        &1000,
+        &admin,
+        &Some(RepaymentSchedule::Installments(vec![
+            &e,
+            Installment { due_timestamp: current_timestamp + 2000, amount: 1100_0000000i128 },
+            Installment { due_timestamp: current_timestamp + 3000, amount: 1100_0000000i128 },
+        ])),
+    );
+    contract.set_min_amount(&admin, &Some(1000_0000000i128));
+    assert_eq!(contract.min_amount(), Some(1000_0000000i128));
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 100_u64;
+    e.ledger().set(current_info);
+
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &500_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    assert_eq!(contract.project_state(), ProjectState::Funding);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 1001_u64;
+    e.ledger().set(current_info);
+
+    assert_eq!(contract.project_state(), ProjectState::Funded);
+    contract.borrower_claim();
+    assert_eq!(eurc_token.balance(&borrower), 1500_0000000i128);
+    assert_eq!(contract.borrower_to_payback(), 1650_0000000i128);
+
+    let schedule = contract.schedule();
+    assert_eq!(schedule.get(0).unwrap().amount, 825_0000000i128);
+    assert_eq!(schedule.get(1).unwrap().amount, 825_0000000i128);
+
+    eurc_token.mint(&borrower, &150_0000000i128);
+    contract.borrower_return(&borrower, &1650_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+    assert_eq!(eurc_token.balance(&admin), 15_0000000i128); // protocol fee
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 1090_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender_2, &0), 545_0000000i128);
+}
+
+#[test]
+fn test_soft_cap_not_reached() {
+    // Here we test that a project below the soft cap refunds lenders
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &500_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_min_amount(&admin, &Some(1000_0000000i128));
+
+    let current_timestamp: u64 = e.ledger().timestamp();
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &500_0000000i128);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 1001_u64;
+    e.ledger().set(current_info);
+
+    assert_eq!(contract.project_state(), ProjectState::Refunding);
+    contract.lender_claim(&lender, &0);
+    assert_eq!(eurc_token.balance(&lender), 500_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #119)")]
+fn test_soft_cap_over_target() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_min_amount(&admin, &Some(3000_0000000i128));
+}
+
+#[test]
+fn test_withdraw_pledge() {
+    // Here we test that lenders can take back their pledge while funding
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let lender_3 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    eurc_token.mint(&lender, &500_0000000i128);
+    eurc_token.mint(&lender_2, &500_0000000i128);
+    eurc_token.mint(&lender_3, &500_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_pledge_lock_period(&admin, &100);
+
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &500_0000000i128);
+    contract.lend(&lender_2, &0, &500_0000000i128);
+    contract.lend(&lender_3, &0, &500_0000000i128);
+
+    contract.withdraw_pledge(&lender_2, &0, &200_0000000i128);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "withdraw_pledge"), lender_2.clone()).into_val(&e),
+                200_0000000i128.into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(contract.balance(&lender_2), 300_0000000i128);
+    assert_eq!(eurc_token.balance(&lender_2), 200_0000000i128);
+    assert_eq!(contract.total_supply(), 1300_0000000i128);
+    assert_eq!(contract.number_of_lenders(), 3);
+
+    contract.withdraw_pledge(&lender, &0, &500_0000000i128);
+    assert_eq!(contract.total_supply(), 800_0000000i128);
+    assert_eq!(eurc_token.balance(&contract.address), 800_0000000i128);
+    assert_eq!(contract.number_of_lenders(), 2);
+    assert_eq!(contract.get_lenders(&0, &10), vec![&e, lender_3.clone(), lender_2.clone()]);
+
+    contract.lend(&lender, &0, &500_0000000i128);
+    assert_eq!(contract.get_lenders(&0, &10), vec![&e, lender_3, lender_2, lender]);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #121)")]
+fn test_withdraw_pledge_locked() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &500_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_pledge_lock_period(&admin, &100);
+
+    let current_timestamp: u64 = e.ledger().timestamp();
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &500_0000000i128);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 901_u64;
+    e.ledger().set(current_info);
+    contract.withdraw_pledge(&lender, &0, &500_0000000i128);
+}
+
+#[test]
+fn test_withdraw_pledge_tranche_minimum() {
+    // Here we test that a partial withdrawal leaves the minimum in the tranche it is taken from
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &600_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_tranches(&admin, &vec![
+        &e,
+        Tranche { target_amount: 1200_0000000i128, reward_rate: 500 },
+        Tranche { target_amount: 800_0000000i128, reward_rate: 1500 },
+    ]);
+    contract.set_lend_limits(&admin, &100_0000000i128, &None, &None);
+
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &300_0000000i128);
+    contract.lend(&lender, &1, &300_0000000i128);
+
+    assert_eq!(
+        contract.try_withdraw_pledge(&lender, &0, &250_0000000i128),
+        Err(Ok(Error::LendBelowMinimum.into()))
+    );
+    contract.withdraw_pledge(&lender, &0, &300_0000000i128);
+    assert_eq!(contract.tranche_balance(&0, &lender), 0);
+    assert_eq!(contract.balance(&lender), 300_0000000i128);
+}
+
+#[test]
+fn test_kyc() {
+    // Here we test the allowlist mode with permanent and expiring approvals
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let kyc_provider = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &500_0000000i128);
+    eurc_token.mint(&lender_2, &500_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_kyc_provider(&admin, &kyc_provider);
+    contract.set_kyc_required(&admin, &true);
+    assert!(!contract.is_kyc_approved(&lender));
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    contract.kyc_approve(&lender);
+    contract.kyc_attest(&lender_2, &(current_timestamp + 500));
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "kyc_approved"), lender_2.clone()).into_val(&e),
+                (current_timestamp + 500).into_val(&e)
+            )
+        ]
+    );
+    assert_eq!(contract.kyc_status(&lender), KycStatus::Approved);
+    assert_eq!(contract.kyc_status(&lender_2), KycStatus::Approved);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 100_u64;
+    e.ledger().set(current_info);
+
+    contract.lend(&lender, &0, &500_0000000i128);
+    contract.lend(&lender_2, &0, &500_0000000i128);
+    contract.transfer(&lender, &lender_2, &100_0000000i128);
+    assert_eq!(contract.balance(&lender_2), 600_0000000i128);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 501_u64;
+    e.ledger().set(current_info);
+    assert_eq!(contract.kyc_status(&lender_2), KycStatus::Expired);
+    assert!(!contract.is_kyc_approved(&lender_2));
+
+    contract.kyc_revoke(&lender);
+    assert_eq!(contract.kyc_status(&lender), KycStatus::None);
+
+    contract.set_kyc_required(&admin, &false);
+    assert!(contract.is_kyc_approved(&lender_2));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #122)")]
+fn test_kyc_transfer_not_approved() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let kyc_provider = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &500_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_kyc_provider(&admin, &kyc_provider);
+    contract.set_kyc_required(&admin, &true);
+    contract.kyc_approve(&lender);
+
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &500_0000000i128);
+    contract.transfer(&lender, &lender_2, &100_0000000i128);
+}
+
+#[test]
+fn test_lend_limits() {
+    // Here we test the per-lender minimum, maximum and concentration caps
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let lender_3 = Address::generate(&e);
+    let lender_4 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &1000_0000000i128);
+    eurc_token.mint(&lender_3, &1000_0000000i128);
+    eurc_token.mint(&lender_4, &1000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    assert_eq!(
+        contract.try_set_lend_limits(&admin, &100_0000000i128, &Some(50_0000000i128), &None),
+        Err(Ok(Error::InvalidLendLimits.into()))
+    );
+    contract.set_lend_limits(&admin, &100_0000000i128, &Some(800_0000000i128), &Some(3500));
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "project_field_changed"),).into_val(&e),
+                (ProjectField::MaxConcentration(None), ProjectField::MaxConcentration(Some(3500))).into_val(&e)
+            )
+        ]
+    );
+    advance_time(&e, 100);
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::MaxLendAmount(None)),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+
+    let project_info = contract.get_project_info();
+    assert_eq!(project_info.min_lend_amount, 100_0000000i128);
+    assert_eq!(project_info.max_lend_amount, Some(800_0000000i128));
+    assert_eq!(project_info.max_concentration, Some(3500));
+
+    contract.lend(&lender, &0, &600_0000000i128);
+    contract.lend(&lender, &0, &100_0000000i128);
+    contract.lend(&lender_2, &0, &600_0000000i128);
+    contract.lend(&lender_3, &0, &650_0000000i128);
+    contract.transfer(&lender_3, &lender_2, &50_0000000i128);
+    assert_eq!(contract.balance(&lender_2), 650_0000000i128);
+
+    // the last lend fills the target and is allowed below the minimum
+    contract.lend(&lender_4, &0, &50_0000000i128);
+    assert_eq!(contract.total_supply(), 2000_0000000i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #124)")]
+fn test_lend_below_minimum() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_lend_limits(&admin, &100_0000000i128, &Some(800_0000000i128), &Some(3500));
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &50_0000000i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #126)")]
+fn test_transfer_concentration_exceeded() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &1000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &eurc_token.address, 2000_0000000i128, None);
+    contract.set_lend_limits(&admin, &100_0000000i128, &Some(800_0000000i128), &Some(3500));
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &700_0000000i128);
+    contract.lend(&lender_2, &0, &200_0000000i128);
+    contract.transfer(&lender_2, &lender, &100_0000000i128);
+}
+
+#[test]
+fn test_simple_interest() {
+    // Here we test interest accruing from the disbursement to each return
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let config = InterestConfig { mode: InterestMode::Simple, apr: 1000, compounding_period: 0 };
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &2000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    contract.set_interest_config(&admin, &config);
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &2000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    assert_eq!(contract.borrower_to_payback(), 2000_0000000i128);
+
+    let disbursed_at: u64 = e.ledger().timestamp();
+    advance_time(&e, 365 * 24 * 3600 / 2);
+    assert_eq!(contract.borrower_to_payback(), 2100_0000000i128);
+
+    // interest is paid first, the protocol fee is taken from it
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(contract.borrower_to_payback(), 1000_0000000i128);
+    assert_eq!(eurc_token.balance(&admin), 10_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 1090_0000000i128);
+
+    // LP tokens are only burned once the principal is repaid
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.balance(&lender), 2000_0000000i128);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = disbursed_at + 365 * 24 * 3600;
+    e.ledger().set(current_info);
+    assert_eq!(contract.borrower_to_payback(), 1050_0000000i128);
+
+    eurc_token.mint(&borrower, &150_0000000i128);
+    contract.borrower_return(&borrower, &1050_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 1045_0000000i128);
+
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.balance(&lender), 0);
+    assert_eq!(eurc_token.balance(&lender), 2135_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+}
+
+#[test]
+fn test_compound_interest() {
+    // Here we test interest compounding over whole periods
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let config = InterestConfig { mode: InterestMode::Compound, apr: 1200, compounding_period: 30 * 24 * 3600 };
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &2000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    assert_eq!(
+        contract.try_set_interest_config(&admin, &InterestConfig { apr: 10001, ..config.clone() }),
+        Err(Ok(Error::InvalidInterestConfig.into()))
+    );
+    contract.set_interest_config(&admin, &config);
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &2000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    advance_time(&e, 2 * 30 * 24 * 3600 + 100);
+    assert_eq!(contract.borrower_to_payback(), 2039_6466128i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #128)")]
+fn test_interest_config_with_schedule() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    contract.init_project(
+        &borrower,
+        &eurc_token.address,
+        &eurc_token.address,
+        &0,
+        &2000_0000000i128,
+        &(current_timestamp + 100_u64),
+        &(current_timestamp + 1000_u64),
+        &1000,
+        &admin,
+        &Some(RepaymentSchedule::Amortized(AmortizationParams {
+            first_due_timestamp: current_timestamp + 2000,
+            period: 1000,
+            number_of_installments: 2,
+        })),
+    );
+    contract.set_interest_config(&admin, &InterestConfig { mode: InterestMode::Simple, apr: 1000, compounding_period: 0 });
+}
+
+#[test]
+fn test_tranches() {
+    // Here we test that returns fill the senior tranche before the junior one
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let senior = Address::generate(&e);
+    let junior = Address::generate(&e);
+    let junior_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&senior, &1200_0000000i128);
+    eurc_token.mint(&junior, &800_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    contract.set_tranches(&admin, &vec![
+        &e,
+        Tranche { target_amount: 1200_0000000i128, reward_rate: 500 },
+        Tranche { target_amount: 800_0000000i128, reward_rate: 1500 },
+    ]);
+    advance_time(&e, 100);
+    contract.lend(&senior, &0, &1200_0000000i128);
+    contract.lend(&junior, &1, &800_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    assert_eq!(contract.balance(&senior), 1200_0000000i128);
+    assert_eq!(contract.tranche_balance(&1, &junior), 800_0000000i128);
+    assert_eq!(contract.tranche_supply(&0), 1200_0000000i128);
+    assert_eq!(contract.borrower_to_payback(), 2180_0000000i128);
+
+    contract.borrower_return(&borrower, &1500_0000000i128);
+    assert_eq!(contract.tranche_to_payback(&0), 0);
+    assert_eq!(contract.tranche_to_payback(&1), 680_0000000i128);
+    assert_eq!(eurc_token.balance(&admin), 6_0000000i128 + 3_1304347i128); // protocol fee
+    assert_eq!(contract.lender_available_to_claim(&senior, &0), 1254_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&junior, &1), 236_8695653i128);
+    assert_eq!(contract.lender_available_to_claim(&junior, &0), 0);
+
+    contract.lender_claim(&senior, &0);
+    assert_eq!(contract.balance(&senior), 0);
+    assert_eq!(eurc_token.balance(&senior), 1254_0000000i128);
+
+    contract.transfer_tranche(&junior, &junior_2, &1, &400_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&junior, &1), 236_8695653i128);
+    assert_eq!(contract.lender_available_to_claim(&junior_2, &1), 0);
+
+    eurc_token.mint(&borrower, &180_0000000i128);
+    contract.borrower_return(&borrower, &680_0000000i128);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+    contract.lender_claim(&junior, &1);
+    contract.lender_claim(&junior_2, &1);
+    assert_eq!(contract.total_supply(), 0);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #131)")]
+fn test_tranches_plain_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let senior = Address::generate(&e);
+    let junior = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&senior, &1200_0000000i128);
+    eurc_token.mint(&junior, &800_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    contract.set_tranches(&admin, &vec![
+        &e,
+        Tranche { target_amount: 1200_0000000i128, reward_rate: 500 },
+        Tranche { target_amount: 800_0000000i128, reward_rate: 1500 },
+    ]);
+    advance_time(&e, 100);
+    contract.lend(&senior, &0, &1200_0000000i128);
+    contract.lend(&junior, &1, &800_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    contract.transfer(&senior, &junior, &100_0000000i128);
+}
+
+#[test]
+fn test_dust() {
+    // Here we test that claims round down and the remainder is tracked as dust
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lenders = [Address::generate(&e), Address::generate(&e), Address::generate(&e)];
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&borrower, &200_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    advance_time(&e, 100);
+
+    let amounts = [666_6666667i128, 666_6666667i128, 666_6666666i128];
+    for (lender, amount) in lenders.iter().zip(amounts) {
+        eurc_token.mint(lender, &amount);
+        contract.lend(lender, &0, &amount);
+    }
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    contract.borrower_return(&borrower, &700_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lenders[0], &0), 231_2121212);
+    assert_eq!(contract.lender_available_to_claim(&lenders[2], &0), 231_2121212);
+    contract.lender_claim(&lenders[0], &0);
+    contract.lender_claim(&lenders[1], &0);
+
+    contract.borrower_return(&borrower, &1500_0000000i128);
+    for lender in lenders.iter() {
+        contract.lender_claim(lender, &0);
+        assert_eq!(contract.balance(lender), 0);
+    }
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+
+    // everything returned minus the protocol fee is either paid out or dust
+    let paid: i128 = lenders.iter().map(|lender| eurc_token.balance(lender)).sum();
+    assert_eq!(eurc_token.balance(&admin), 19_9999999i128);
+    assert_eq!(paid + contract.dust(), 2180_0000001i128);
+    assert_eq!(eurc_token.balance(&contract.address), contract.dust());
+    assert_eq!(contract.dust(), 3);
+}
+
+#[test]
+fn test_claim_after_transfer_out() {
+    // Here we test that a holder who transferred all LP tokens away keeps the earned returns
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &1000_0000000i128);
+    eurc_token.mint(&borrower, &200_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    contract.transfer(&lender, &lender_2, &1000_0000000i128);
+    contract.borrower_return(&borrower, &1100_0000000i128);
+
+    // the last LP holder claiming does not close the project yet
+    contract.lender_claim(&lender_2, &0);
+    assert_eq!(eurc_token.balance(&lender_2), 1635_0000000i128);
+    assert_eq!(contract.total_supply(), 0);
+    assert_eq!(contract.project_state(), ProjectState::Repaid);
+    assert_eq!(contract.dust(), 0);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 545_0000000i128);
+
+    // a mistaken transfer is surplus, the return of the former holder is not
+    let to = Address::generate(&e);
+    eurc_token.mint(&contract.address, &5_0000000i128);
+    assert_eq!(contract.surplus(), 5_0000000i128);
+    assert_eq!(
+        contract.try_rescue_tokens(&admin, &eurc_token.address, &to, &5_0000001i128),
+        Err(Ok(Error::InsufficientSurplus.into()))
+    );
+    contract.rescue_tokens(&admin, &eurc_token.address, &to, &5_0000000i128);
+    assert_eq!(eurc_token.balance(&to), 5_0000000i128);
+
+    contract.lender_claim(&lender, &0);
+    assert_eq!(eurc_token.balance(&lender), 545_0000000i128);
+    assert_eq!(contract.dust(), 0);
+    assert_eq!(eurc_token.balance(&contract.address), 0);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+}
+
+#[test]
+fn test_lender_registry() {
+    // Here we test the paginated lender list, pruning and lender positions
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let lender_3 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &500_0000000i128);
+    eurc_token.mint(&lender_2, &700_0000000i128);
+    eurc_token.mint(&lender_3, &800_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    let current_timestamp: u64 = e.ledger().timestamp();
+    advance_time(&e, 100);
+
+    contract.lend(&lender, &0, &500_0000000i128);
+    contract.lend(&lender_2, &0, &700_0000000i128);
+    contract.lend(&lender_3, &0, &800_0000000i128);
+    assert_eq!(contract.get_lenders(&0, &2), vec![&e, lender.clone(), lender_2.clone()]);
+    assert_eq!(contract.get_lenders(&2, &10), vec![&e, lender_3.clone()]);
+    assert_eq!(contract.get_lenders(&3, &10).len(), 0);
+    assert_eq!(contract.get_lenders(&u32::MAX, &u32::MAX).len(), 0);
+
+    // a holder without LP tokens is removed from the list
+    contract.transfer(&lender, &lender_2, &500_0000000i128);
+    assert_eq!(contract.number_of_lenders(), 2);
+    assert_eq!(contract.get_lenders(&0, &10), vec![&e, lender_3.clone(), lender_2.clone()]);
+
+    // empty transfers do not add the recipient
+    let stranger = Address::generate(&e);
+    contract.transfer(&lender_2, &stranger, &0);
+    contract.transfer(&stranger, &lender, &0);
+    assert_eq!(contract.number_of_lenders(), 2);
+    assert_eq!(
+        contract.lender_position(&lender),
+        LenderPosition {
+            balance: 0,
+            claimed: 0,
+            available: 0,
+            share: 0,
+            first_lend_at: Some(current_timestamp + 100),
+        }
+    );
+    assert_eq!(contract.lender_position(&admin).first_lend_at, None);
+
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    contract.lender_claim(&lender_2, &0);
+    assert_eq!(
+        contract.lender_position(&lender_2),
+        LenderPosition {
+            balance: 1200_0000000i128,
+            claimed: 654_0000000i128,
+            available: 0,
+            share: 6000,
+            first_lend_at: Some(current_timestamp + 100),
+        }
+    );
+    assert_eq!(contract.lender_position(&lender_3).available, 436_0000000i128);
+}
+
+#[test]
+fn test_project_summary() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &2000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+    let summary: ProjectSummary = contract.project_summary();
+    assert_eq!(summary.state, ProjectState::Created);
+    assert_eq!(summary.seconds_to_start, 100);
+    assert_eq!(summary.seconds_to_end, 1000);
+    assert_eq!(summary.remaining_to_target, 2000_0000000i128);
+    assert_eq!(summary.collateral_status, CollateralStatus::Missing);
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = current_timestamp + 100_u64;
+    e.ledger().set(current_info);
+    contract.lend(&lender, &0, &1500_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    assert_eq!(
+        contract.project_summary(),
+        ProjectSummary {
+            state: ProjectState::Funding,
+            target_amount: 2000_0000000i128,
+            raised_amount: 1500_0000000i128,
+            remaining_to_target: 500_0000000i128,
+            total_return: 0,
+            fee_accumulated: 0,
+            outstanding_payback: 0,
+            number_of_lenders: 1,
+            seconds_to_start: 0,
+            seconds_to_end: 900,
+            collateral_status: CollateralStatus::Held,
+        }
+    );
+
+    contract.lend(&lender, &0, &500_0000000i128);
+    contract.borrower_claim();
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    let summary: ProjectSummary = contract.project_summary();
+    assert_eq!(summary.state, ProjectState::Repaying);
+    assert_eq!(summary.raised_amount, 2000_0000000i128);
+    assert_eq!(summary.remaining_to_target, 0);
+    assert_eq!(summary.total_return, 1090_0000000i128);
+    assert_eq!(summary.fee_accumulated, 10_0000000i128);
+    assert_eq!(summary.outstanding_payback, 1100_0000000i128);
+}
+
+#[test]
+fn test_dry_run_status() {
+    // Here we test that the status queries return the error the call would fail with
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &2000_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    assert_eq!(contract.lend_status(&lender, &0, &100_0000000i128), LendStatus::NotStarted);
+
+    advance_time(&e, 100);
+    assert_eq!(contract.lend_status(&lender, &0, &-1), LendStatus::InvalidAmount);
+    assert_eq!(contract.lend_status(&lender, &1, &100_0000000i128), LendStatus::InvalidTranche);
+    assert_eq!(
+        contract.lend_status(&lender, &0, &2100_0000000i128),
+        LendStatus::TargetOverreached
+    );
+    assert_eq!(contract.lend_status(&lender, &0, &2000_0000000i128), LendStatus::Available);
+    assert_eq!(contract.lender_claim_status(&lender, &0), LenderClaimStatus::NothingToClaim);
+    assert_eq!(contract.borrower_return_status(&100_0000000i128), BorrowerReturnStatus::InvalidState);
+
+    contract.lend(&lender, &0, &2000_0000000i128);
+    assert_eq!(contract.lend_status(&lender, &0, &1), LendStatus::TargetOverreached);
+    assert_eq!(contract.borrower_claim_status(), BorrowerClaimStatus::NoCollateral);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+
+    assert_eq!(contract.lend_status(&lender, &0, &1), LendStatus::AlreadyFinished);
+    assert_eq!(
+        contract.borrower_return_status(&2200_0000001i128),
+        BorrowerReturnStatus::ReturnOverreached
+    );
+    assert_eq!(contract.borrower_return_status(&2200_0000000i128), BorrowerReturnStatus::Available);
+    assert_eq!(contract.borrower_return_status(&-1), BorrowerReturnStatus::InvalidAmount);
+    assert_eq!(
+        contract.try_borrower_return(&borrower, &-1),
+        Err(Ok(Error::OnlyPositiveValue.into()))
+    );
+    let result = contract.try_borrower_return(&borrower, &2200_0000001i128);
+    assert_eq!(result, Err(Ok(Error::ReturnOverreached.into())));
+
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(contract.lender_claim_status(&lender, &0), LenderClaimStatus::Available);
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.lender_claim_status(&lender, &0), LenderClaimStatus::NothingToClaim);
+}
+
+#[test]
+fn test_migrate() {
+    // Here we test that a contract with the first storage layout is migrated to the current one
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+
+    let contract_id = e.register_contract(None, EnerDAOToken {});
+    let contract = EnerDAOTokenClient::new(&e, &contract_id);
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    assert_eq!(contract.version(), Version { code_version: 2, schema_version: 2 });
+    assert_eq!(contract.try_migrate(), Err(Ok(Error::AlreadyMigrated.into())));
+
+    // a fully funded project written by the first code version
+    let current_info: LedgerInfo = e.ledger().get();
+    let current_timestamp: u64 = current_info.timestamp;
+    e.as_contract(&contract_id, || {
+        e.storage().instance().remove(&DataKey::SchemaVersion);
+        e.storage().persistent().set(
+            &LegacyDataKey::ProjectInfo,
+            &LegacyProjectInfo {
+                borrower: borrower.clone(),
+                lend_token_address: eurc_token.address.clone(),
+                collateral_nft_address: nft.address.clone(),
+                collateral_id: 0,
+                target_amount: 2000_0000000i128,
+                start_timestamp: current_timestamp,
+                final_timestamp: current_timestamp + 1000_u64,
+                reward_rate: 1000,
+                treasury_address: admin.clone(),
+            },
+        );
+        e.storage().persistent().set(&LegacyDataKey::TargetNotReached, &false);
+        e.storage().persistent().set(&DataKey::TotalSupply, &2000_0000000i128);
+        e.storage().persistent().set(&DataKey::NumberOfLenders, &1_u128);
+        e.storage().persistent().set(&DataKey::LenderIndex(lender.clone()), &1_u128);
+        e.storage().persistent().set(&DataKey::LenderAddress(1), &lender);
+        e.storage().persistent().set(&DataKey::Balance(lender.clone()), &2000_0000000i128);
+    });
+    assert_eq!(contract.version().schema_version, 1);
+
+    contract.migrate();
+    assert_eq!(contract.version(), Version { code_version: 2, schema_version: 2 });
+    assert_eq!(contract.get_project_info().target_amount, 2000_0000000i128);
+    assert_eq!(contract.get_project_info().min_lend_amount, 0);
+    assert_eq!(contract.get_project_info().max_lend_amount, None);
+    assert_eq!(contract.project_state(), ProjectState::Funded);
+    assert_eq!(contract.tranche_balance(&0, &lender), 2000_0000000i128);
+    e.as_contract(&contract_id, || {
+        assert!(!e.storage().persistent().has(&LegacyDataKey::TargetNotReached));
+    });
+    assert_eq!(contract.try_migrate(), Err(Ok(Error::AlreadyMigrated.into())));
+
+    // a disbursed project can not be migrated
+    e.as_contract(&contract_id, || {
+        e.storage().instance().remove(&DataKey::SchemaVersion);
+        e.storage().persistent().set(&LegacyDataKey::BorrowerClaimed, &true);
+    });
+    assert_eq!(contract.try_migrate(), Err(Ok(Error::UnsupportedMigration.into())));
+}
+
+#[test]
+fn test_roles() {
+    // Here we test that every holder of a granted role takes over from the admin
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let treasurer = Address::generate(&e);
+    let treasurer_2 = Address::generate(&e);
+    let operator = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    assert_eq!(contract.role_holder_count(&Role::Treasurer), 0);
+
+    // until a role is granted the admin acts in it
+    contract.set_protocol_fee(&admin, &500);
+    assert_eq!(e.auths()[0].0, admin);
+
+    contract.grant_role(&Role::Treasurer, &treasurer);
+    contract.grant_role(&Role::Treasurer, &treasurer_2);
+    contract.grant_role(&Role::Operator, &operator);
+    assert_eq!(contract.role_holder_count(&Role::Treasurer), 2);
+    assert!(contract.has_role(&Role::Treasurer, &treasurer));
+    assert!(contract.has_role(&Role::Treasurer, &treasurer_2));
+    assert!(!contract.has_role(&Role::Treasurer, &admin));
+    assert!(!contract.has_role(&Role::Operator, &treasurer));
+
+    // every holder acts in the role, the admin no longer does
+    contract.set_protocol_fee(&treasurer, &1000);
+    assert_eq!(e.auths()[0].0, treasurer);
+    contract.rescue_tokens(&treasurer_2, &eurc_token.address, &treasurer, &0);
+    assert_eq!(e.auths()[0].0, treasurer_2);
+    assert_eq!(
+        contract.try_set_protocol_fee(&admin, &1500),
+        Err(Ok(Error::RoleNotGranted.into()))
+    );
+    assert_eq!(
+        contract.try_set_min_amount(&treasurer, &Some(1000_0000000i128)),
+        Err(Ok(Error::RoleNotGranted.into()))
+    );
+    contract.set_min_amount(&operator, &Some(1000_0000000i128));
+    assert_eq!(e.auths()[0].0, operator);
+    contract.set_kyc_required(&admin, &true);
+    assert_eq!(e.auths()[0].0, admin);
+
+    // granting a role again keeps a single membership
+    contract.grant_role(&Role::Treasurer, &treasurer);
+    assert_eq!(contract.role_holder_count(&Role::Treasurer), 2);
+
+    assert_eq!(
+        contract.try_revoke_role(&Role::Treasurer, &operator),
+        Err(Ok(Error::RoleNotGranted.into()))
+    );
+    contract.revoke_role(&Role::Treasurer, &treasurer);
+    assert!(!contract.has_role(&Role::Treasurer, &treasurer));
+    assert!(contract.has_role(&Role::Treasurer, &treasurer_2));
+    assert_eq!(
+        contract.try_set_protocol_fee(&treasurer, &1500),
+        Err(Ok(Error::RoleNotGranted.into()))
+    );
+    contract.set_protocol_fee(&treasurer_2, &1500);
+    assert_eq!(e.auths()[0].0, treasurer_2);
+
+    // the admin acts in the role again once its last holder is revoked
+    contract.revoke_role(&Role::Treasurer, &treasurer_2);
+    assert_eq!(contract.role_holder_count(&Role::Treasurer), 0);
+    contract.set_protocol_fee(&admin, &2000);
+    assert_eq!(e.auths()[0].0, admin);
+}
+
+#[test]
+fn test_governance() {
+    // Here we test that material changes after the funding started need a passed vote
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let lender_3 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let new_borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &500_0000000i128);
+    eurc_token.mint(&lender_3, &100_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    assert_eq!(
+        contract.try_set_governance_config(&admin, &GovernanceConfig { quorum: 2000, threshold: 10000, voting_period: 500 }),
+        Err(Ok(GovernanceError::InvalidGovernanceConfig.into()))
+    );
+    contract.set_governance_config(&admin, &GovernanceConfig { quorum: 2000, threshold: 5000, voting_period: 500 });
+    let final_timestamp: u64 = contract.get_project_info().final_timestamp;
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp += 100;
+    e.ledger().set(current_info);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &500_0000000i128);
+
+    // the project info can no longer be rewritten directly
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::FinalTimestamp(final_timestamp + 1000)),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+    assert_eq!(
+        contract.try_propose(&admin, &ProposalAction::ExtendDeadline(final_timestamp)),
+        Err(Ok(GovernanceError::InvalidProposal.into()))
+    );
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp += 10;
+    e.ledger().set(current_info);
+    let extend = contract.propose(&admin, &ProposalAction::ExtendDeadline(final_timestamp + 1000));
+    let rate = contract.propose(&admin, &ProposalAction::SetRewardRate(2000));
+    let replace = contract.propose(&admin, &ProposalAction::ReplaceBorrower(new_borrower.clone()));
+    assert_eq!(contract.get_proposal(&extend).supply, 1500_0000000i128);
+
+    // votes are weighted by the balance before the proposal
+    contract.lend(&lender_3, &0, &100_0000000i128);
+    assert_eq!(contract.voting_power(&lender, &extend), 1000_0000000i128);
+    assert_eq!(contract.voting_power(&lender_3, &extend), 0);
+
+    // changes within one timestamp share the last checkpoint
+    contract.transfer(&lender, &lender_3, &1_0000000i128);
+    contract.transfer(&lender_3, &lender, &1_0000000i128);
+    e.as_contract(&contract.address, || {
+        let count: u32 = e
+            .storage()
+            .persistent()
+            .get(&GovernanceDataKey::BalanceCheckpointCount(lender.clone()))
+            .unwrap();
+        assert_eq!(count, 2);
+    });
+    assert_eq!(contract.voting_power(&lender, &extend), 1000_0000000i128);
+    assert_eq!(
+        contract.try_vote(&lender_3, &extend, &true),
+        Err(Ok(GovernanceError::NoVotingPower.into()))
+    );
+    contract.vote(&lender, &extend, &true);
+    contract.vote(&lender_2, &extend, &false);
+    assert_eq!(
+        contract.try_vote(&lender, &extend, &true),
+        Err(Ok(GovernanceError::AlreadyVoted.into()))
+    );
+    contract.vote(&lender_2, &rate, &false);
+    assert_eq!(contract.get_proposal(&extend).votes_for, 1000_0000000i128);
+    assert_eq!(contract.get_proposal(&extend).votes_against, 500_0000000i128);
+    assert_eq!(contract.get_proposal(&extend).status, ProposalStatus::Active);
+    assert_eq!(
+        contract.try_execute_proposal(&extend),
+        Err(Ok(GovernanceError::ProposalNotPassed.into()))
+    );
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp += 500;
+    e.ledger().set(current_info);
+    assert_eq!(
+        contract.try_vote(&lender, &rate, &true),
+        Err(Ok(GovernanceError::VotingClosed.into()))
+    );
+    assert_eq!(contract.get_proposal(&extend).status, ProposalStatus::Passed);
+    // against the threshold
+    assert_eq!(contract.get_proposal(&rate).status, ProposalStatus::Rejected);
+    // below the quorum
+    assert_eq!(contract.get_proposal(&replace).status, ProposalStatus::Rejected);
+    assert_eq!(
+        contract.try_execute_proposal(&replace),
+        Err(Ok(GovernanceError::ProposalNotPassed.into()))
+    );
+
+    contract.execute_proposal(&extend);
+    assert_eq!(contract.get_project_info().final_timestamp, final_timestamp + 1000);
+    assert_eq!(contract.get_proposal(&extend).status, ProposalStatus::Executed);
+    assert_eq!(
+        contract.try_execute_proposal(&extend),
+        Err(Ok(GovernanceError::ProposalNotPassed.into()))
+    );
+
+    // an active proposal can be cancelled
+    let replace = contract.propose(&admin, &ProposalAction::ReplaceBorrower(new_borrower.clone()));
+    contract.cancel_proposal(&admin, &replace);
+    assert_eq!(contract.get_proposal(&replace).status, ProposalStatus::Cancelled);
+    assert_eq!(
+        contract.try_vote(&lender, &replace, &true),
+        Err(Ok(GovernanceError::VotingClosed.into()))
+    );
+    assert_eq!(contract.get_project_info().borrower, borrower);
+
+    // the treasury changes through a proposal once anyone has lent
+    let treasury = Address::generate(&e);
+    let set_treasury = contract.propose(&admin, &ProposalAction::SetTreasury(treasury.clone()));
+    contract.vote(&lender, &set_treasury, &true);
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp += 500;
+    e.ledger().set(current_info);
+    contract.execute_proposal(&set_treasury);
+    assert_eq!(contract.get_project_info().treasury_address, treasury);
+}
+
+#[test]
+fn test_pause() {
+    // Here we test pausing single functions and the emergency withdrawal after a long pause
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let pauser = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &500_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    assert_eq!(contract.try_set_max_pause_duration(&admin, &0), Err(Ok(Error::InvalidPauseDuration.into())));
+    contract.set_max_pause_duration(&admin, &1000);
+    contract.grant_role(&Role::Pauser, &pauser);
+
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &1000_0000000i128);
+
+    contract.pause(&pauser, &vec![&e, PausableFunction::Lend, PausableFunction::Transfer]);
+    assert_eq!(e.auths()[0].0, pauser);
+    assert!(contract.is_paused(&PausableFunction::Lend));
+    assert!(!contract.is_paused(&PausableFunction::WithdrawPledge));
+    assert_eq!(contract.lend_status(&lender_2, &0, &500_0000000i128), LendStatus::Paused);
+    assert_eq!(
+        contract.try_lend(&lender_2, &0, &500_0000000i128),
+        Err(Ok(Error::FunctionPaused.into()))
+    );
+    assert_eq!(
+        contract.try_transfer(&lender, &lender_2, &100_0000000i128),
+        Err(Ok(Error::FunctionPaused.into()))
+    );
+    contract.withdraw_pledge(&lender, &0, &200_0000000i128);
+
+    contract.unpause(&pauser, &vec![&e, PausableFunction::Lend]);
+    contract.lend(&lender_2, &0, &500_0000000i128);
+    assert!(contract.is_paused(&PausableFunction::Transfer));
+
+    // a long pause of transfers alone does not open the escape hatch
+    advance_time(&e, 1001);
+    assert_eq!(contract.project_state(), ProjectState::Refunding);
+    assert!(!contract.is_emergency());
+    assert_eq!(contract.try_emergency_withdraw(&lender), Err(Ok(Error::NoEmergency.into())));
+
+    // it opens once the claims are paused for longer than the maximum
+    contract.pause(&pauser, &vec![&e, PausableFunction::LenderClaim]);
+    assert_eq!(contract.lender_claim_status(&lender, &0), LenderClaimStatus::Paused);
+    advance_time(&e, 1000);
+    assert!(!contract.is_emergency());
+    advance_time(&e, 1);
+    assert!(contract.is_emergency());
+
+    // lenders get their pledges, not a share of the tokens sent by mistake
+    eurc_token.mint(&contract.address, &130_0000000i128);
+    contract.emergency_withdraw(&lender);
+    assert_eq!(eurc_token.balance(&lender), 1000_0000000i128);
+    assert_eq!(contract.balance(&lender), 0);
+    contract.emergency_withdraw(&lender_2);
+    assert_eq!(eurc_token.balance(&lender_2), 500_0000000i128);
+    assert_eq!(contract.total_supply(), 0);
+    assert_eq!(eurc_token.balance(&contract.address), 130_0000000i128);
+    assert_eq!(contract.try_emergency_withdraw(&lender), Err(Ok(Error::NothingToClaim.into())));
+}
+
+#[test]
+fn test_emergency_withdraw_returns() {
+    // Here we test that the emergency withdrawal pays the pending returns only once
+    // and the lender keeps the LP tokens for the later returns
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &1000_0000000i128);
+    eurc_token.mint(&borrower, &200_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    contract.set_max_pause_duration(&admin, &1000);
+    advance_time(&e, 100);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    contract.borrower_return(&borrower, &1100_0000000i128);
+
+    contract.pause(&admin, &vec![&e, PausableFunction::LenderClaim]);
+    advance_time(&e, 1001);
+    contract.emergency_withdraw(&lender);
+    assert_eq!(eurc_token.balance(&lender), 545_0000000i128);
+    assert_eq!(contract.balance(&lender), 1000_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 0);
+
+    contract.unpause(&admin, &vec![&e, PausableFunction::LenderClaim]);
+    assert_eq!(
+        contract.try_lender_claim(&lender, &0),
+        Err(Ok(Error::NothingToClaim.into()))
+    );
+
+    // the lender still shares in the returns after the withdrawal
+    contract.borrower_return(&borrower, &1100_0000000i128);
+    assert_eq!(contract.lender_available_to_claim(&lender, &0), 545_0000000i128);
+    contract.lender_claim(&lender, &0);
+    assert_eq!(eurc_token.balance(&lender), 1090_0000000i128);
+    contract.lender_claim(&lender_2, &0);
+    assert_eq!(eurc_token.balance(&lender_2), 1090_0000000i128);
+    assert_eq!(eurc_token.balance(&contract.address), 0);
+    assert_eq!(contract.project_state(), ProjectState::Closed);
+}
+
+#[test]
+fn test_project_fields() {
+    // Here we test the project info validation and which fields can change in which phase
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let treasury = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+
+    let current_info: LedgerInfo = e.ledger().get();
+    let now: u64 = current_info.timestamp;
+    let contract = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+    contract.initialize(&admin, &7, &"LP EnerDAO".into_val(&e), &"LPE".into_val(&e));
+    let init = |target_amount: i128, start_timestamp: u64, final_timestamp: u64, reward_rate: i128| {
+        contract.try_init_project(
+            &borrower,
+            &eurc_token.address,
+            &nft.address,
+            &0,
+            &target_amount,
+            &start_timestamp,
+            &final_timestamp,
+            &reward_rate,
+            &admin,
+            &None,
+        )
+    };
+    assert_eq!(init(0, now + 100, now + 1000, 1000), Err(Ok(ProjectInfoError::InvalidTargetAmount.into())));
+    assert_eq!(init(2000_0000000i128, now + 1000, now + 100, 1000), Err(Ok(ProjectInfoError::InvalidTimestamps.into())));
+    assert_eq!(init(2000_0000000i128, now + 100, now + 1000, -1), Err(Ok(ProjectInfoError::InvalidRewardRate.into())));
+    assert_eq!(init(2000_0000000i128, now + 100, now + 1000, 10001), Err(Ok(ProjectInfoError::InvalidRewardRate.into())));
+    assert!(init(2000_0000000i128, now + 100, now + 1000, 1000).is_ok());
+    assert_eq!(
+        init(2000_0000000i128, now + 100, now + 1000, 1000),
+        Err(Ok(ProjectInfoError::ProjectAlreadyInitialized.into()))
+    );
+
+    // before the funding every field can change, each change is published
+    contract.set_project_field(&admin, &ProjectField::TargetAmount(3000_0000000i128));
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                contract.address.clone(),
+                (Symbol::new(&e, "project_field_changed"),).into_val(&e),
+                (
+                    ProjectField::TargetAmount(2000_0000000i128),
+                    ProjectField::TargetAmount(3000_0000000i128)
+                )
+                    .into_val(&e)
+            )
+        ]
+    );
+    contract.set_project_field(&admin, &ProjectField::StartTimestamp(now + 50));
+    assert_eq!(contract.get_project_info().target_amount, 3000_0000000i128);
+    assert_eq!(contract.get_project_info().start_timestamp, now + 50);
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::FinalTimestamp(now + 50)),
+        Err(Ok(ProjectInfoError::InvalidTimestamps.into()))
+    );
+
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp = now + 100;
+    e.ledger().set(current_info);
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::StartTimestamp(now + 200)),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+    contract.set_project_field(&admin, &ProjectField::RewardRate(1500));
+
+    // a wrong collateral address counts as no collateral and can be fixed
+    contract.set_project_field(&admin, &ProjectField::CollateralNftAddress(eurc_token.address.clone()));
+    assert_eq!(contract.collateral_status(), CollateralStatus::Missing);
+    contract.set_project_field(&admin, &ProjectField::CollateralNftAddress(Address::generate(&e)));
+    assert_eq!(contract.collateral_status(), CollateralStatus::Missing);
+    assert_eq!(contract.project_summary().collateral_status, CollateralStatus::Missing);
+    contract.set_project_field(&admin, &ProjectField::CollateralNftAddress(nft.address.clone()));
+
+    // the collateral is fixed once the contract holds it
+    contract.set_project_field(&admin, &ProjectField::CollateralId(1));
+    nft.mint(&contract.address, &1, &String::from_str(&e, "https://uri.com"));
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::CollateralNftAddress(eurc_token.address.clone())),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+
+    // once anyone has lent no field can change
+    contract.lend(&lender, &0, &1000_0000000i128);
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::TargetAmount(2000_0000000i128)),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::LendTokenAddress(nft.address.clone())),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+    assert_eq!(
+        contract.try_set_project_field(&admin, &ProjectField::TreasuryAddress(treasury.clone())),
+        Err(Ok(ProjectInfoError::FieldFrozen.into()))
+    );
+    assert_eq!(contract.get_project_info().treasury_address, admin);
+    assert_eq!(contract.get_project_info().reward_rate, 1500);
+}
+
+#[test]
+fn test_rescue_tokens() {
+    // Here we test that only the surplus of the lend token can be rescued and lenders stay whole
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+
+    let admin = Address::generate(&e);
+    let lender = Address::generate(&e);
+    let lender_2 = Address::generate(&e);
+    let borrower = Address::generate(&e);
+    let rescuer = Address::generate(&e);
+    let eurc_token = create_custom_token(&e, &admin, &7);
+    let other_token = create_custom_token(&e, &admin, &7);
+    let nft = create_nft(&e, &admin);
+    eurc_token.mint(&lender, &1000_0000000i128);
+    eurc_token.mint(&lender_2, &1000_0000000i128);
+    eurc_token.mint(&borrower, &200_0000000i128);
+
+    let contract = create_project(&e, &admin, &borrower, &eurc_token.address, &nft.address, 2000_0000000i128, None);
+    let mut current_info: LedgerInfo = e.ledger().get();
+    current_info.timestamp += 100;
+    e.ledger().set(current_info);
+    contract.lend(&lender, &0, &1000_0000000i128);
+    contract.lend(&lender_2, &0, &1000_0000000i128);
+
+    // pledges are not surplus, tokens sent by mistake are
+    assert_eq!(contract.surplus(), 0);
+    eurc_token.mint(&contract.address, &50_0000000i128);
+    other_token.mint(&contract.address, &70_0000000i128);
+    assert_eq!(contract.surplus(), 50_0000000i128);
+    assert_eq!(
+        contract.try_rescue_tokens(&admin, &eurc_token.address, &rescuer, &50_0000001i128),
+        Err(Ok(Error::InsufficientSurplus.into()))
+    );
+    contract.rescue_tokens(&admin, &eurc_token.address, &rescuer, &50_0000000i128);
+    contract.rescue_tokens(&admin, &other_token.address, &rescuer, &70_0000000i128);
+    assert_eq!(eurc_token.balance(&rescuer), 50_0000000i128);
+    assert_eq!(other_token.balance(&rescuer), 70_0000000i128);
+
+    nft.mint(&contract.address, &0, &String::from_str(&e, "https://uri.com"));
+    contract.borrower_claim();
+    assert_eq!(eurc_token.balance(&borrower), 2200_0000000i128);
+
+    // unclaimed returns are not surplus either
+    contract.borrower_return(&borrower, &2200_0000000i128);
+    eurc_token.mint(&contract.address, &30_0000000i128);
+    assert_eq!(contract.surplus(), 30_0000000i128);
+    assert_eq!(
+        contract.try_rescue_tokens(&admin, &eurc_token.address, &rescuer, &31_0000000i128),
+        Err(Ok(Error::InsufficientSurplus.into()))
+    );
+    contract.rescue_tokens(&admin, &eurc_token.address, &rescuer, &30_0000000i128);
+
+    contract.lender_claim(&lender, &0);
+    assert_eq!(contract.surplus(), 0);
+    contract.lender_claim(&lender_2, &0);
+    assert_eq!(eurc_token.balance(&lender), 1090_0000000i128);
+    assert_eq!(eurc_token.balance(&lender_2), 1090_0000000i128);
+    assert_eq!(contract.surplus(), eurc_token.balance(&contract.address));
 }
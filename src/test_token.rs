@@ -1,10 +1,13 @@
 #![cfg(test)]
 extern crate std;
 
-use crate::{contract::EnerDAOToken, contract::EnerDAOTokenClient};
+use crate::{
+    contract::EnerDAOToken, contract::EnerDAOTokenClient, errors::Error, storage_types::AdminAction,
+    timelock::MAX_TIMELOCK_DELAY,
+};
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger},
     Address, Env, IntoVal, Symbol
 };
 
@@ -106,7 +109,7 @@ fn test() {
     assert_eq!(token.balance(&user1), 500);
     assert_eq!(token.balance(&user3), 300);
 
-    token.set_admin(&admin2);
+    token.propose_admin(&admin2);
     assert_eq!(
         e.auths(),
         std::vec![(
@@ -114,7 +117,7 @@ fn test() {
             AuthorizedInvocation {
                 function: AuthorizedFunction::Contract((
                     token.address.clone(),
-                    symbol_short!("set_admin"),
+                    Symbol::new(&e, "propose_admin"),
                     (&admin2,).into_val(&e),
                 )),
                 sub_invocations: std::vec![]
@@ -122,6 +125,23 @@ fn test() {
         )]
     );
 
+    token.accept_admin();
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin2.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    Symbol::new(&e, "accept_admin"),
+                    ().into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(token.pending_admin(), None);
+
     // Increase to 500
     token.approve(&user2, &user3, &500, &200);
     assert_eq!(token.allowance(&user2, &user3), 500);
@@ -201,7 +221,6 @@ fn test_burn() {
 }
 
 #[test]
-#[should_panic(expected = "insufficient balance")]
 fn transfer_insufficient_balance() {
     let e = Env::default();
     e.mock_all_auths();
@@ -214,11 +233,18 @@ fn transfer_insufficient_balance() {
     token.mint(&user1, &1000);
     assert_eq!(token.balance(&user1), 1000);
 
-    token.transfer(&user1, &user2, &1001);
+    assert_eq!(
+        token.try_transfer(&user1, &user2, &1001),
+        Err(Ok(Error::InsufficientBalance.into()))
+    );
+    assert_eq!(
+        token.try_burn(&user1, &1001),
+        Err(Ok(Error::InsufficientBalance.into()))
+    );
+    assert_eq!(token.balance(&user1), 1000);
 }
 
 #[test]
-#[should_panic(expected = "insufficient allowance")]
 fn transfer_from_insufficient_allowance() {
     let e = Env::default();
     e.mock_all_auths();
@@ -235,7 +261,45 @@ fn transfer_from_insufficient_allowance() {
     token.approve(&user1, &user3, &100, &200);
     assert_eq!(token.allowance(&user1, &user3), 100);
 
-    token.transfer_from(&user3, &user1, &user2, &101);
+    assert_eq!(
+        token.try_transfer_from(&user3, &user1, &user2, &101),
+        Err(Ok(Error::InsufficientAllowance.into()))
+    );
+    assert_eq!(token.allowance(&user1, &user3), 100);
+}
+
+#[test]
+fn approve_expired_ledger() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.ledger().with_mut(|li| li.sequence_number = 300);
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    assert_eq!(
+        token.try_approve(&user1, &user2, &100, &200),
+        Err(Ok(Error::InvalidExpirationLedger.into()))
+    );
+    // clearing an allowance does not need a future expiration ledger
+    token.approve(&user1, &user2, &0, &200);
+    assert_eq!(token.allowance(&user1, &user2), 0);
+}
+
+#[test]
+fn mint_not_initialized() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let user1 = Address::generate(&e);
+    let token = EnerDAOTokenClient::new(&e, &e.register_contract(None, EnerDAOToken {}));
+
+    assert_eq!(
+        token.try_mint(&user1, &100),
+        Err(Ok(Error::NotInitialized.into()))
+    );
 }
 
 #[test]
@@ -267,3 +331,63 @@ fn test_zero_allowance() {
     token.transfer_from(&spender, &from, &spender, &0);
     assert!(token.get_allowance(&from, &spender).is_none());
 }
+
+#[test]
+fn test_timelock() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin1 = Address::generate(&e);
+    let admin2 = Address::generate(&e);
+    let user = Address::generate(&e);
+    let token = create_token(&e, &admin1);
+    let rescued = create_token(&e, &admin1);
+    rescued.mint(&token.address, &1000);
+
+    // without a timelock sensitive actions take effect right away
+    token.rescue_tokens(&admin1, &rescued.address, &user, &100);
+    assert_eq!(rescued.balance(&user), 100);
+    assert_eq!(
+        token.try_set_timelock_delay(&(MAX_TIMELOCK_DELAY + 1)),
+        Err(Ok(Error::InvalidTimelockDelay.into()))
+    );
+    token.set_timelock_delay(&3600);
+    assert_eq!(token.timelock_delay(), 3600);
+    assert_eq!(
+        token.try_rescue_tokens(&admin1, &rescued.address, &user, &100),
+        Err(Ok(Error::TimelockRequired.into()))
+    );
+    assert_eq!(token.try_set_timelock_delay(&0), Err(Ok(Error::TimelockRequired.into())));
+    assert_eq!(token.try_mint(&user, &100), Err(Ok(Error::TimelockRequired.into())));
+    assert_eq!(token.try_burn(&user, &100), Err(Ok(Error::TimelockRequired.into())));
+
+    // queued actions wait for the delay and can be cancelled
+    let id = token.queue_action(&admin1, &AdminAction::RescueTokens(rescued.address.clone(), user.clone(), 100));
+    let mint = token.queue_action(&admin1, &AdminAction::Mint(user.clone(), 100));
+    let cancelled = token.queue_action(&admin1, &AdminAction::SetTimelockDelay(0));
+    assert_eq!(token.queued_action(&id).unwrap().eta, e.ledger().timestamp() + 3600);
+    assert_eq!(token.try_execute_action(&admin1, &id), Err(Ok(Error::TimelockNotExpired.into())));
+    token.cancel_action(&admin1, &cancelled);
+    assert_eq!(token.queued_action(&cancelled), None);
+    assert_eq!(token.try_execute_action(&admin1, &cancelled), Err(Ok(Error::ActionNotQueued.into())));
+
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    token.execute_action(&admin1, &id);
+    assert_eq!(rescued.balance(&user), 200);
+    token.execute_action(&admin1, &mint);
+    assert_eq!(token.balance(&user), 100);
+    assert_eq!(token.queued_action(&id), None);
+    assert_eq!(token.try_execute_action(&admin1, &id), Err(Ok(Error::ActionNotQueued.into())));
+
+    // the new admin can accept once the delay has passed
+    token.propose_admin(&admin2);
+    assert_eq!(token.try_accept_admin(), Err(Ok(Error::TimelockNotExpired.into())));
+    token.cancel_admin_proposal();
+    assert_eq!(token.try_accept_admin(), Err(Ok(Error::NoPendingAdmin.into())));
+
+    token.propose_admin(&admin2);
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    token.accept_admin();
+    assert_eq!(token.pending_admin(), None);
+    assert_eq!(token.try_accept_admin(), Err(Ok(Error::NoPendingAdmin.into())));
+}
@@ -0,0 +1,85 @@
+use crate::errors::Error;
+use crate::storage_types::{
+    DataKey, PendingAdmin, QueuedAction, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Env};
+
+pub(crate) const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+
+// Seconds a sensitive admin action has to wait between being
+// queued and being executed, no timelock by default
+pub fn read_timelock_delay(e: &Env) -> u64 {
+    let key = DataKey::TimelockDelay;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_timelock_delay(e: &Env, delay: u64) {
+    if delay > MAX_TIMELOCK_DELAY {
+        panic_with_error!(e, Error::InvalidTimelockDelay)
+    }
+    let key = DataKey::TimelockDelay;
+    e.storage().instance().set(&key, &delay);
+}
+
+// Timestamp from which an action queued now can be executed
+pub fn timelock_eta(e: &Env) -> u64 {
+    match e.ledger().timestamp().checked_add(read_timelock_delay(e)) {
+        Some(eta) => eta,
+        None => panic_with_error!(e, Error::MathOverflow),
+    }
+}
+
+// Sensitive actions can only be called directly while there is no timelock,
+// otherwise they have to go through `queue_action`
+pub fn require_no_timelock(e: &Env) {
+    if read_timelock_delay(e) > 0 {
+        panic_with_error!(e, Error::TimelockRequired)
+    }
+}
+
+pub fn read_pending_admin(e: &Env) -> Option<PendingAdmin> {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_pending_admin(e: &Env, pending_admin: &PendingAdmin) {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().set(&key, pending_admin);
+}
+
+pub fn remove_pending_admin(e: &Env) {
+    let key = DataKey::PendingAdmin;
+    e.storage().instance().remove(&key);
+}
+
+pub fn read_queued_action(e: &Env, id: u32) -> QueuedAction {
+    let key = DataKey::QueuedAction(id);
+    match e.storage().persistent().get(&key) {
+        Some(queued_action) => queued_action,
+        None => panic_with_error!(e, Error::ActionNotQueued),
+    }
+}
+
+pub fn has_queued_action(e: &Env, id: u32) -> bool {
+    let key = DataKey::QueuedAction(id);
+    e.storage().persistent().has(&key)
+}
+
+// Stores the action under the next free id and returns the id
+pub fn write_queued_action(e: &Env, queued_action: &QueuedAction) -> u32 {
+    let id_key = DataKey::NextActionId;
+    let id: u32 = e.storage().instance().get(&id_key).unwrap_or(0);
+    e.storage().instance().set(&id_key, &(id + 1));
+
+    let key = DataKey::QueuedAction(id);
+    e.storage().persistent().set(&key, queued_action);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    id
+}
+
+pub fn remove_queued_action(e: &Env, id: u32) {
+    let key = DataKey::QueuedAction(id);
+    e.storage().persistent().remove(&key);
+}
@@ -0,0 +1,246 @@
+use crate::errors::Error;
+use crate::math::{mul_div, Rounding};
+use crate::storage_types::{
+    DataKey, ProjectInfo, ReturnCheckpoint, Tranche, TrancheDataKey, BALANCE_BUMP_AMOUNT,
+    BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, vec, Address, Env, Vec};
+
+// Precision of the return index
+const INDEX_SCALE: i128 = 1_000_000_000_000_000_000;
+
+// Tranches in order of seniority. A project without tranches
+// has a single one with its own target amount and reward rate.
+pub fn read_tranches(e: &Env) -> Vec<Tranche> {
+    let key = DataKey::Tranches;
+    match e.storage().persistent().get(&key) {
+        Some(tranches) => tranches,
+        None => {
+            let project_info: ProjectInfo =
+                e.storage().persistent().get(&DataKey::ProjectInfo).unwrap();
+            vec![
+                e,
+                Tranche {
+                    target_amount: project_info.target_amount,
+                    reward_rate: project_info.reward_rate,
+                },
+            ]
+        }
+    }
+}
+
+pub fn write_tranches(e: &Env, tranches: &Vec<Tranche>) {
+    let key = DataKey::Tranches;
+    e.storage().persistent().set(&key, tranches);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn number_of_tranches(e: &Env) -> u32 {
+    let key = DataKey::Tranches;
+    match e.storage().persistent().get::<DataKey, Vec<Tranche>>(&key) {
+        Some(tranches) => tranches.len(),
+        None => 1,
+    }
+}
+
+pub fn read_tranche(e: &Env, tranche: u32) -> Tranche {
+    match read_tranches(e).get(tranche) {
+        Some(tranche) => tranche,
+        None => panic_with_error!(e, Error::InvalidTranche),
+    }
+}
+
+fn read_amount(e: &Env, key: &DataKey) -> i128 {
+    e.storage().persistent().get(key).unwrap_or(0)
+}
+
+fn write_amount(e: &Env, key: &DataKey, amount: i128) {
+    e.storage().persistent().set(key, &amount);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+fn tranche_key(tranche: u32, address: Address) -> TrancheDataKey {
+    TrancheDataKey { tranche, address }
+}
+
+pub fn read_tranche_balance(e: &Env, tranche: u32, addr: Address) -> i128 {
+    read_amount(e, &DataKey::TrancheBalance(tranche_key(tranche, addr)))
+}
+
+pub fn receive_tranche_balance(e: &Env, tranche: u32, addr: Address, amount: i128) {
+    let key = DataKey::TrancheBalance(tranche_key(tranche, addr));
+    write_amount(e, &key, read_amount(e, &key) + amount);
+}
+
+pub fn spend_tranche_balance(e: &Env, tranche: u32, addr: Address, amount: i128) {
+    let key = DataKey::TrancheBalance(tranche_key(tranche, addr));
+    let balance: i128 = read_amount(e, &key);
+    if balance < amount {
+        panic_with_error!(e, Error::InsufficientBalance)
+    }
+    write_amount(e, &key, balance - amount);
+}
+
+pub fn read_tranche_supply(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::TrancheSupply(tranche))
+}
+
+pub fn add_tranche_supply(e: &Env, tranche: u32, amount: i128) {
+    let key = DataKey::TrancheSupply(tranche);
+    write_amount(e, &key, read_amount(e, &key) + amount);
+}
+
+// LP supply of the tranche when the funds were disbursed
+pub fn read_tranche_raised(e: &Env, tranche: u32) -> Option<i128> {
+    e.storage().persistent().get(&DataKey::TrancheRaised(tranche))
+}
+
+pub fn write_tranche_raised(e: &Env, tranche: u32, amount: i128) {
+    write_amount(e, &DataKey::TrancheRaised(tranche), amount);
+}
+
+// Everything the waterfall allocated to the tranche, including protocol fees
+pub fn read_tranche_repaid(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::TrancheRepaid(tranche))
+}
+
+pub fn add_tranche_repaid(e: &Env, tranche: u32, amount: i128) {
+    let key = DataKey::TrancheRepaid(tranche);
+    write_amount(e, &key, read_amount(e, &key) + amount);
+}
+
+// Part of the repaid amount that goes to the lenders of the tranche
+pub fn read_tranche_return(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::TrancheReturn(tranche))
+}
+
+pub fn add_tranche_return(e: &Env, tranche: u32, amount: i128) {
+    let key = DataKey::TrancheReturn(tranche);
+    write_amount(e, &key, read_amount(e, &key) + amount);
+}
+
+// Part of the principal of the tranche covered by the returns so far
+pub fn read_tranche_principal_repaid(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::TranchePrincipalRepaid(tranche))
+}
+
+pub fn write_tranche_principal_repaid(e: &Env, tranche: u32, amount: i128) {
+    write_amount(e, &DataKey::TranchePrincipalRepaid(tranche), amount);
+}
+
+// Returns paid out to the lenders of the tranche, never above the tranche return
+pub fn read_tranche_paid(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::TranchePaid(tranche))
+}
+
+pub fn add_tranche_paid(e: &Env, tranche: u32, amount: i128) {
+    let key = DataKey::TranchePaid(tranche);
+    write_amount(e, &key, read_amount(e, &key) + amount);
+}
+
+// Returns settled to the holders of the tranche and not claimed yet
+pub fn read_tranche_pending(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::TranchePending(tranche))
+}
+
+fn add_tranche_pending(e: &Env, tranche: u32, amount: i128) {
+    let key = DataKey::TranchePending(tranche);
+    write_amount(e, &key, read_amount(e, &key) + amount);
+}
+
+// True while any holder, including former ones, has returns to claim
+pub fn has_pending_returns(e: &Env) -> bool {
+    (0..number_of_tranches(e)).any(|tranche| read_tranche_pending(e, tranche) > 0)
+}
+
+// Returns the holders of the tranche can still claim. While the tranche has
+// LP supply this is everything not paid out yet, rounding included, without
+// supply only the pending returns of former holders are left.
+pub fn read_unclaimed_return(e: &Env, tranche: u32) -> i128 {
+    if read_tranche_supply(e, tranche) > 0 {
+        read_tranche_return(e, tranche) - read_tranche_paid(e, tranche)
+    } else {
+        read_tranche_pending(e, tranche)
+    }
+}
+
+// Rounding remainders of repaid tranches left in the contract
+pub fn read_dust(e: &Env) -> i128 {
+    (0..number_of_tranches(e))
+        .map(|tranche| read_amount(e, &DataKey::Dust(tranche)))
+        .sum()
+}
+
+// Books what the rounding left of the return of a tranche without LP supply.
+// The pending returns of former holders are not dust, so booking again after
+// they claimed gives the same amount.
+pub fn book_dust(e: &Env, tranche: u32) {
+    let dust: i128 = read_tranche_return(e, tranche)
+        - read_tranche_paid(e, tranche)
+        - read_tranche_pending(e, tranche);
+    write_amount(e, &DataKey::Dust(tranche), dust);
+}
+
+// Cumulative return per LP token of the tranche, in INDEX_SCALE precision
+pub fn read_return_index(e: &Env, tranche: u32) -> i128 {
+    read_amount(e, &DataKey::ReturnIndex(tranche))
+}
+
+// Spreads a return over the current LP supply of the tranche
+pub fn distribute_return(e: &Env, tranche: u32, amount: i128) {
+    let supply: i128 = read_tranche_supply(e, tranche);
+    if supply > 0 {
+        let index: i128 = read_return_index(e, tranche);
+        let increase: i128 = mul_div(e, amount, INDEX_SCALE, supply, Rounding::Down);
+        write_amount(e, &DataKey::ReturnIndex(tranche), index + increase);
+    }
+}
+
+fn read_checkpoint(e: &Env, tranche: u32, addr: Address) -> ReturnCheckpoint {
+    let key = DataKey::ReturnCheckpoint(tranche_key(tranche, addr));
+    e.storage().persistent().get(&key).unwrap_or(ReturnCheckpoint {
+        index: 0,
+        pending: 0,
+    })
+}
+
+fn write_checkpoint(e: &Env, tranche: u32, addr: Address, checkpoint: &ReturnCheckpoint) {
+    let key = DataKey::ReturnCheckpoint(tranche_key(tranche, addr));
+    e.storage().persistent().set(&key, checkpoint);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Returns earned by the holder and not claimed yet
+pub fn read_pending_return(e: &Env, tranche: u32, addr: Address) -> i128 {
+    let checkpoint: ReturnCheckpoint = read_checkpoint(e, tranche, addr.clone());
+    let balance: i128 = read_tranche_balance(e, tranche, addr);
+    let index: i128 = read_return_index(e, tranche);
+    checkpoint.pending + mul_div(e, balance, index - checkpoint.index, INDEX_SCALE, Rounding::Down)
+}
+
+// Moves the returns earned with the current balance to the pending amount,
+// has to be called before the balance of the holder changes
+pub fn settle(e: &Env, tranche: u32, addr: Address) {
+    let index: i128 = read_return_index(e, tranche);
+    let checkpoint: ReturnCheckpoint = read_checkpoint(e, tranche, addr.clone());
+    if checkpoint.index == index {
+        return;
+    }
+    let pending: i128 = read_pending_return(e, tranche, addr.clone());
+    add_tranche_pending(e, tranche, pending - checkpoint.pending);
+    write_checkpoint(e, tranche, addr, &ReturnCheckpoint { index, pending });
+}
+
+// Takes a claimed amount off the pending returns of a settled holder
+pub fn spend_pending_return(e: &Env, tranche: u32, addr: Address, amount: i128) {
+    let mut checkpoint: ReturnCheckpoint = read_checkpoint(e, tranche, addr.clone());
+    checkpoint.pending -= amount;
+    write_checkpoint(e, tranche, addr, &checkpoint);
+    add_tranche_pending(e, tranche, -amount);
+}
@@ -0,0 +1,134 @@
+use crate::balance::read_balance;
+use crate::errors::Error;
+use crate::governance::{write_balance_checkpoint, write_supply_checkpoint};
+use crate::state::transition;
+use crate::storage_types::{DataKey, ProjectInfo, ProjectState};
+use crate::tranche::{add_tranche_supply, receive_tranche_balance};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+// Version of the contract code, bumped with every release
+pub(crate) const CODE_VERSION: u32 = 2;
+// Version of the storage layout the code expects, `migrate` brings
+// the data of an upgraded contract to it
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+
+// Keys of the first layout that are no longer used, only `migrate` reads them
+#[derive(Clone)]
+#[contracttype]
+pub enum LegacyDataKey {
+    ProjectInfo,
+    ClaimAvailable,
+    TargetNotReached,
+    BorrowerClaimed,
+}
+
+// ProjectInfo before the per-lender limits were added
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LegacyProjectInfo {
+    pub borrower: Address,
+    pub lend_token_address: Address,
+    pub collateral_nft_address: Address,
+    pub collateral_id: u128,
+    pub target_amount: i128,
+    pub start_timestamp: u64,
+    pub final_timestamp: u64,
+    pub reward_rate: i128,
+    pub treasury_address: Address,
+}
+
+// Contracts deployed before the schema version was stored use the first layout
+pub fn read_schema_version(e: &Env) -> u32 {
+    let key = DataKey::SchemaVersion;
+    e.storage().instance().get(&key).unwrap_or(1)
+}
+
+pub fn write_schema_version(e: &Env, version: u32) {
+    let key = DataKey::SchemaVersion;
+    e.storage().instance().set(&key, &version);
+}
+
+fn read_flag(e: &Env, key: &LegacyDataKey) -> bool {
+    e.storage().persistent().get(key).unwrap_or(false)
+}
+
+// Schema 1 kept the lifecycle in flags and had no tranches. Only projects
+// that were not disbursed yet can be migrated, the claim accounting of a
+// disbursed project can not be carried over.
+fn migrate_v1(e: &Env) {
+    if read_flag(e, &LegacyDataKey::BorrowerClaimed) {
+        panic_with_error!(e, Error::UnsupportedMigration)
+    }
+
+    if let Some(legacy) = e
+        .storage()
+        .persistent()
+        .get::<LegacyDataKey, LegacyProjectInfo>(&LegacyDataKey::ProjectInfo)
+    {
+        let project_info = ProjectInfo {
+            borrower: legacy.borrower,
+            lend_token_address: legacy.lend_token_address,
+            collateral_nft_address: legacy.collateral_nft_address,
+            collateral_id: legacy.collateral_id,
+            target_amount: legacy.target_amount,
+            start_timestamp: legacy.start_timestamp,
+            final_timestamp: legacy.final_timestamp,
+            reward_rate: legacy.reward_rate,
+            treasury_address: legacy.treasury_address,
+            min_lend_amount: 0,
+            max_lend_amount: None,
+            max_concentration: None,
+        };
+        e.storage()
+            .persistent()
+            .set(&DataKey::ProjectInfo, &project_info);
+
+        let total_supply: i128 = e
+            .storage()
+            .persistent()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0);
+        if read_flag(e, &LegacyDataKey::TargetNotReached) {
+            transition(e, ProjectState::Refunding);
+        } else if total_supply >= project_info.target_amount {
+            transition(e, ProjectState::Funding);
+            transition(e, ProjectState::Funded);
+        }
+
+        // every lender so far lent to the single tranche
+        add_tranche_supply(e, 0, total_supply);
+        write_supply_checkpoint(e, total_supply);
+        let number_of_lenders: u128 = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NumberOfLenders)
+            .unwrap_or(0);
+        for i in 1..=number_of_lenders {
+            let lender: Address = e
+                .storage()
+                .persistent()
+                .get(&DataKey::LenderAddress(i))
+                .unwrap();
+            let balance: i128 = read_balance(e, lender.clone());
+            receive_tranche_balance(e, 0, lender.clone(), balance);
+            write_balance_checkpoint(e, lender, balance);
+        }
+    }
+
+    e.storage().persistent().remove(&LegacyDataKey::ClaimAvailable);
+    e.storage().persistent().remove(&LegacyDataKey::TargetNotReached);
+}
+
+// Runs the migrations from the stored schema version up to SCHEMA_VERSION,
+// returns the version the data was migrated from
+pub fn migrate(e: &Env) -> u32 {
+    let from: u32 = read_schema_version(e);
+    if from >= SCHEMA_VERSION {
+        panic_with_error!(e, Error::AlreadyMigrated)
+    }
+    if from < 2 {
+        migrate_v1(e);
+    }
+    write_schema_version(e, SCHEMA_VERSION);
+    from
+}